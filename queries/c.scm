@@ -0,0 +1,12 @@
+; Spell-checkable regions of C sources.
+
+[
+  (identifier)
+  (field_identifier)
+  (type_identifier)
+  (statement_identifier)
+] @spell.identifier
+
+(comment) @spell.comment
+
+(string_content) @spell.string
@@ -0,0 +1,13 @@
+; Spell-checkable regions of CSS stylesheets.
+
+[
+  (class_name)
+  (id_name)
+  (property_name)
+  (tag_name)
+  (feature_name)
+] @spell.identifier
+
+(comment) @spell.comment
+
+(string_content) @spell.string
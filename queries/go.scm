@@ -0,0 +1,16 @@
+; Spell-checkable regions of Go sources.
+
+[
+  (identifier)
+  (field_identifier)
+  (type_identifier)
+  (package_identifier)
+  (label_name)
+] @spell.identifier
+
+(comment) @spell.comment
+
+[
+  (interpreted_string_literal_content)
+  (raw_string_literal_content)
+] @spell.string
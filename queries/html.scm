@@ -0,0 +1,7 @@
+; Spell-checkable regions of HTML documents.
+
+(comment) @spell.comment
+
+(attribute_value) @spell.string
+
+(text) @spell.text
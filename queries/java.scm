@@ -0,0 +1,13 @@
+; Spell-checkable regions of Java sources.
+
+[
+  (identifier)
+  (type_identifier)
+] @spell.identifier
+
+[
+  (line_comment)
+  (block_comment)
+] @spell.comment
+
+(string_fragment) @spell.string
@@ -0,0 +1,54 @@
+; Spell-checkable regions of JavaScript sources.
+
+[
+  (identifier)
+  (property_identifier)
+  (private_property_identifier)
+  (shorthand_property_identifier)
+  (shorthand_property_identifier_pattern)
+  (statement_identifier)
+] @spell.identifier
+
+[
+  (comment)
+  (html_comment)
+] @spell.comment
+
+(string_fragment) @spell.string
+
+(jsx_text) @spell.text
+
+; Tagged templates are checked with the grammar of their tag, like
+; css`.button { color: red; }`, with the fragments around substitutions
+; parsed together, and strings annotated with a comment like
+; /* json */ with the grammar it names.
+
+(call_expression
+  function: (identifier) @_tag
+  arguments: (template_string (string_fragment) @injection.content)
+  (#eq? @_tag "css")
+  (#set! injection.language "css")
+  (#set! injection.combined))
+
+(call_expression
+  function: (identifier) @_tag
+  arguments: (template_string (string_fragment) @injection.content)
+  (#eq? @_tag "html")
+  (#set! injection.language "html")
+  (#set! injection.combined))
+
+(call_expression
+  function: (identifier) @_tag
+  arguments: (template_string (string_fragment) @injection.content)
+  (#eq? @_tag "sql")
+  (#set! injection.language "sql")
+  (#set! injection.combined))
+
+((comment) @_annotation
+  .
+  [
+    (string (string_fragment) @injection.content)
+    (template_string (string_fragment) @injection.content)
+  ]
+  (#match? @_annotation "^/\\*\\s*json\\s*\\*/$")
+  (#set! injection.language "json"))
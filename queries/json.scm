@@ -0,0 +1,5 @@
+; Spell-checkable regions of JSON documents.
+
+(comment) @spell.comment
+
+(string_content) @spell.string
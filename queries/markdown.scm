@@ -0,0 +1,20 @@
+; Spell-checkable regions of Markdown documents. Only the block structure is
+; parsed here, and the prose in it is checked with `markdown_inline.scm`.
+
+; Paragraphs, headings and list items.
+((inline) @injection.content
+  (#set! injection.language "markdown_inline"))
+
+((pipe_table_cell) @injection.content
+  (#set! injection.language "markdown_inline"))
+
+; Code fences are checked with the grammar their info string names, if
+; checking code blocks is enabled.
+(fenced_code_block
+  (info_string (language) @injection.language)
+  (code_fence_content) @injection.content)
+
+; HTML comments hold directives like `<!-- rspell:disable -->`. Other HTML is
+; not checked.
+((html_block) @spell.comment
+  (#match? @spell.comment "^<!--"))
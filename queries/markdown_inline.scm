@@ -0,0 +1,21 @@
+; Spell-checkable regions of the inline content of Markdown documents:
+; paragraphs, headings, list items and table cells, including link text and
+; image descriptions.
+
+(inline) @spell.text
+
+; Code, URLs and HTML tags are not prose.
+[
+  (code_span)
+  (link_destination)
+  (uri_autolink)
+  (email_autolink)
+  (latex_block)
+] @spell.ignore
+
+((html_tag) @spell.ignore
+  (#not-match? @spell.ignore "^<!--"))
+
+; HTML comments hold directives like `<!-- rspell:ignore -->`.
+((html_tag) @spell.comment
+  (#match? @spell.comment "^<!--"))
@@ -0,0 +1,7 @@
+; Spell-checkable regions of Python sources.
+
+(identifier) @spell.identifier
+
+(comment) @spell.comment
+
+(string_content) @spell.string
@@ -0,0 +1,33 @@
+; Spell-checkable regions of Rust sources.
+
+[
+  (identifier)
+  (type_identifier)
+  (field_identifier)
+  (shorthand_field_identifier)
+] @spell.identifier
+
+; The names bound by `macro_rules!`, like `$name`.
+(metavariable) @spell.identifier
+
+; Line and block comments, including `///` and `//!` doc comments.
+[
+  (line_comment)
+  (block_comment)
+] @spell.comment
+
+; The content of normal and raw strings, without quotes, `r#` delimiters and
+; escape sequences. Strings in macro bodies like `format!` are parsed too.
+(string_content) @spell.string
+
+; Lifetimes and loop labels are conventionally short, like `'a` or `'de`.
+(lifetime (identifier) @spell.ignore)
+(label (identifier) @spell.ignore)
+
+; Attributes name things defined elsewhere, like derived traits, lints and
+; cfg options, so only the strings in them are checked.
+(attribute (identifier) @spell.ignore)
+(attribute (scoped_identifier name: (identifier) @spell.ignore))
+(attribute arguments: (token_tree (identifier) @spell.ignore))
+(attribute arguments: (token_tree (token_tree (identifier) @spell.ignore)))
+(attribute arguments: (token_tree (token_tree (token_tree (identifier) @spell.ignore))))
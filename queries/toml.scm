@@ -0,0 +1,7 @@
+; Spell-checkable regions of TOML documents.
+
+(bare_key) @spell.identifier
+
+(comment) @spell.comment
+
+(string) @spell.string
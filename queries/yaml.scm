@@ -0,0 +1,10 @@
+; Spell-checkable regions of YAML documents.
+
+(comment) @spell.comment
+
+[
+  (string_scalar)
+  (double_quote_scalar)
+  (single_quote_scalar)
+  (block_scalar)
+] @spell.string
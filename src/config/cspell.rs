@@ -0,0 +1,375 @@
+//! Reading of cspell configuration files, mapped onto rspell's [`Config`].
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{Config, LanguageConfig};
+use crate::{dictionary::flag_words::FlagWordConfig, parsing::language::LANGUAGES};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct CSpellConfig {
+    words: Vec<String>,
+    ignore_words: Vec<String>,
+    ignore_paths: Vec<String>,
+    dictionaries: Vec<String>,
+    dictionary_definitions: Vec<DictionaryDefinition>,
+    language_settings: Vec<LanguageSetting>,
+    ignore_reg_exp_list: Vec<String>,
+    flag_words: Vec<String>,
+    overrides: Vec<Override>,
+    min_word_length: Option<usize>,
+    #[serde(flatten)]
+    unsupported: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DictionaryDefinition {
+    name: String,
+    path: String,
+    #[serde(flatten)]
+    unsupported: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct LanguageSetting {
+    language_id: OneOrMany,
+    enabled: Option<bool>,
+    words: Vec<String>,
+    ignore_words: Vec<String>,
+    dictionaries: Vec<String>,
+    ignore_reg_exp_list: Vec<String>,
+    #[serde(flatten)]
+    unsupported: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Override {
+    filename: OneOrMany,
+    enabled: Option<bool>,
+    words: Vec<String>,
+    ignore_words: Vec<String>,
+    #[serde(flatten)]
+    unsupported: BTreeMap<String, Value>,
+}
+
+/// cspell accepts either a single, possibly comma separated, value or a list
+/// for language ids and file names.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Default for OneOrMany {
+    fn default() -> Self {
+        OneOrMany::Many(Vec::new())
+    }
+}
+
+impl OneOrMany {
+    fn values(&self) -> Vec<&str> {
+        match self {
+            OneOrMany::One(value) => value.split(',').map(str::trim).collect(),
+            OneOrMany::Many(values) => values.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// Keys that have no effect on checking, and are ignored without a warning.
+const IGNORED_KEYS: [&str; 4] = ["$schema", "version", "name", "description"];
+
+/// Maps a cspell (VS Code) language id onto the names of rspell languages.
+fn language_names(language_id: &str) -> Vec<&'static str> {
+    let name = match language_id {
+        "*" => {
+            return LANGUAGES
+                .iter()
+                .filter(|language| language.parent.is_none())
+                .map(|language| language.name)
+                .collect();
+        }
+        "plaintext" => "text",
+        "typescriptreact" => "tsx",
+        "javascriptreact" => "javascript",
+        "jsonc" => "json",
+        other => other,
+    };
+
+    LANGUAGES
+        .iter()
+        .map(|language| language.name)
+        .filter(|language| *language == name)
+        .collect()
+}
+
+/// Parses the content of a `cspell.json` file. Settings rspell has no
+/// equivalent for are left out and reported in `Config::warnings`.
+pub fn parse(content: &str) -> Result<Config> {
+    let cspell: CSpellConfig =
+        serde_json::from_str(&strip_comments(content)).context("Invalid cspell config")?;
+
+    let mut config = Config::default();
+    let mut warnings = Vec::new();
+
+    warn_unsupported(&mut warnings, "", &cspell.unsupported);
+    for definition in &cspell.dictionary_definitions {
+        warn_unsupported(
+            &mut warnings,
+            "dictionaryDefinitions.",
+            &definition.unsupported,
+        );
+    }
+
+    config.words = cspell.words;
+    // Entries use the same `word->replacement` form.
+    config.flag_words = cspell
+        .flag_words
+        .into_iter()
+        .map(FlagWordConfig::Short)
+        .collect();
+    config.ignore_words = cspell.ignore_words;
+    config.ignore_paths = cspell.ignore_paths;
+    // Patterns are `/regex/flags` or names, some of which match presets.
+    config.ignore_patterns.extend(cspell.ignore_reg_exp_list);
+
+    if let Some(min_word_length) = cspell.min_word_length {
+        config.min_word_length = min_word_length;
+    }
+
+    let mut dictionaries = cspell.dictionaries;
+
+    for setting in cspell.language_settings {
+        warn_unsupported(&mut warnings, "languageSettings.", &setting.unsupported);
+
+        if !setting.words.is_empty()
+            || !setting.ignore_words.is_empty()
+            || !setting.dictionaries.is_empty()
+        {
+            warnings.push(format!(
+                "words and dictionaries in languageSettings for {:?} apply to all languages",
+                setting.language_id.values().join(",")
+            ));
+        }
+
+        config.words.extend(setting.words);
+        config.ignore_words.extend(setting.ignore_words);
+        dictionaries.extend(setting.dictionaries);
+
+        if setting.enabled.is_none() && setting.ignore_reg_exp_list.is_empty() {
+            continue;
+        }
+
+        for language_id in setting.language_id.values() {
+            let names = language_names(language_id);
+
+            if names.is_empty() {
+                warnings.push(format!("unsupported languageId {language_id:?}"));
+            }
+
+            for name in names {
+                let language = config
+                    .languages
+                    .entry(name.to_string())
+                    .or_insert_with(LanguageConfig::default);
+
+                if let Some(enabled) = setting.enabled {
+                    language.enabled = enabled;
+                }
+                language
+                    .ignore_patterns
+                    .extend(setting.ignore_reg_exp_list.iter().cloned());
+            }
+        }
+    }
+
+    for name in dictionaries {
+        match cspell
+            .dictionary_definitions
+            .iter()
+            .find(|definition| definition.name == name)
+        {
+            Some(definition) => config.dictionaries.push(definition.path.clone()),
+            None => warnings.push(format!(
+                "cspell's built-in dictionary {name:?} is not available"
+            )),
+        }
+    }
+
+    for override_ in cspell.overrides {
+        warn_unsupported(&mut warnings, "overrides.", &override_.unsupported);
+
+        if override_.enabled == Some(false) {
+            config
+                .ignore_paths
+                .extend(override_.filename.values().into_iter().map(String::from));
+        }
+
+        if !override_.words.is_empty() || !override_.ignore_words.is_empty() {
+            warnings.push(format!(
+                "words in overrides for {:?} apply to all files",
+                override_.filename.values().join(",")
+            ));
+        }
+
+        config.words.extend(override_.words);
+        config.ignore_words.extend(override_.ignore_words);
+    }
+
+    config.warnings = warnings;
+
+    Ok(config)
+}
+
+fn warn_unsupported(warnings: &mut Vec<String>, prefix: &str, keys: &BTreeMap<String, Value>) {
+    warnings.extend(
+        keys.keys()
+            .filter(|key| !IGNORED_KEYS.contains(&key.as_str()))
+            .map(|key| format!("{prefix}{key} is not supported")),
+    );
+}
+
+/// Removes `//` and `/* */` comments, which cspell allows in its JSON files.
+fn strip_comments(content: &str) -> String {
+    let mut stripped = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(char) = chars.next() {
+        if in_string {
+            stripped.push(char);
+
+            match char {
+                '\\' => stripped.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+
+            continue;
+        }
+
+        match (char, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                stripped.push(char);
+            }
+            ('/', Some('/')) => {
+                for char in chars.by_ref() {
+                    if char == '\n' {
+                        stripped.push(char);
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+
+                let mut previous = ' ';
+                for char in chars.by_ref() {
+                    if previous == '*' && char == '/' {
+                        break;
+                    }
+                    previous = char;
+                }
+            }
+            _ => stripped.push(char),
+        }
+    }
+
+    stripped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn maps_cspell_settings() {
+        let config = parse(
+            r#"{
+                // cspell allows comments
+                "version": "0.2",
+                "words": ["rspell"],
+                "ignoreWords": ["tset"],
+                "ignorePaths": ["node_modules/**"],
+                "minWordLength": 4,
+                "dictionaries": ["project-words", "companies"],
+                "dictionaryDefinitions": [
+                    { "name": "project-words", "path": "./words.txt", "addWords": true }
+                ],
+                "flagWords": ["hte->the"],
+                "ignoreRegExpList": ["HexValues", "/\\bJIRA-\\d+/"],
+                "overrides": [{ "filename": "**/*.snap", "enabled": false }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.words, ["rspell"]);
+        assert_eq!(config.ignore_words, ["tset"]);
+        assert_eq!(config.ignore_paths, ["node_modules/**", "**/*.snap"]);
+        assert_eq!(config.min_word_length, 4);
+        assert_eq!(config.dictionaries, ["dictionaries/*", "./words.txt"]);
+        assert_eq!(
+            config.ignore_patterns,
+            ["Urls", "HexValues", "/\\bJIRA-\\d+/"]
+        );
+        assert!(
+            matches!(&config.flag_words[..], [FlagWordConfig::Short(entry)] if entry == "hte->the")
+        );
+        assert_eq!(
+            config.warnings,
+            [
+                "dictionaryDefinitions.addWords is not supported",
+                "cspell's built-in dictionary \"companies\" is not available"
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "lang-markdown", feature = "lang-typescript"))]
+    fn maps_language_settings() {
+        let config = parse(
+            r#"{
+                "languageSettings": [
+                    { "languageId": "markdown,typescriptreact", "enabled": false, "locale": "en" },
+                    { "languageId": "typescript", "ignoreRegExpList": ["UUID"] }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(!config.is_enabled("markdown"));
+        assert!(!config.is_enabled("tsx"));
+        assert!(config.is_enabled("typescript"));
+        assert_eq!(config.ignore_patterns("typescript"), ["Urls", "UUID"]);
+        assert_eq!(
+            config.warnings,
+            ["languageSettings.locale is not supported"]
+        );
+    }
+
+    #[test]
+    fn warns_about_unsupported_keys() {
+        let config = parse(r#"{ "import": ["../cspell.json"], "patterns": [] }"#).unwrap();
+
+        assert_eq!(
+            config.warnings,
+            ["import is not supported", "patterns is not supported"]
+        );
+    }
+
+    #[test]
+    fn strips_comments_outside_strings() {
+        assert_eq!(
+            strip_comments("{ /* a */ \"b\": \"// c /* d */\" // e\n}"),
+            "{  \"b\": \"// c /* d */\" \n}"
+        );
+    }
+}
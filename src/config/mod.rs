@@ -0,0 +1,551 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{self, Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer, de};
+
+use crate::{
+    dictionary::{compound::CompoundOptions, flag_words::FlagWordConfig},
+    parsing::{
+        language::NodeCategory,
+        parser::{CaseSensitivity, Rule, Severity},
+    },
+    reporting::OutputFormat,
+};
+
+mod cspell;
+
+/// The names of the configuration file, in order of precedence. cspell
+/// configurations are read as well, for projects moving over from it.
+pub const CONFIG_FILE_NAMES: [&str; 4] =
+    ["rspell.toml", ".rspell.toml", "cspell.json", ".cspell.json"];
+
+/// Project configuration, read from an `rspell.toml` or `cspell.json` file.
+/// Relative paths are resolved against `root`, the directory the file is in.
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// Words accepted in addition to the dictionaries, and used for
+    /// suggestions.
+    pub words: Vec<String>,
+    /// Words accepted without being suggested.
+    pub ignore_words: Vec<String>,
+    /// Words reported even if a dictionary accepts them, see `FlagWordConfig`.
+    pub flag_words: Vec<FlagWordConfig>,
+    /// Globs of files not to check.
+    pub ignore_paths: Vec<String>,
+    /// Patterns of text not to check, like hashes, see `IgnorePatterns`.
+    pub ignore_patterns: Vec<String>,
+    /// Globs of the dictionaries to load, the base of every language.
+    pub dictionaries: Vec<String>,
+    /// Regular expressions of words accepted by the project, matched against
+    /// whole, lowercased words.
+    pub word_patterns: Vec<String>,
+    /// Further dictionaries stacked between the base and the project's
+    /// words, e.g. for a framework, see `LayeredDictionary`.
+    pub layers: Vec<LayerConfig>,
+    /// Globs of the user's own dictionaries, which take precedence over all
+    /// others. Defaults to `dictionaries/*` in rspell's user config
+    /// directory.
+    pub user_dictionaries: Option<Vec<String>>,
+    /// Words shorter than this are not checked.
+    pub min_word_length: usize,
+    /// Where the casing of dictionary entries like "GitHub" is enforced.
+    pub case_sensitivity: CaseSensitivity,
+    /// Kinds of nodes in which unknown words are accepted if they split into
+    /// dictionary words, like "readfile".
+    pub compound_words: Vec<NodeCategory>,
+    pub compound_min_part_length: usize,
+    pub compound_max_parts: usize,
+    /// Whether code blocks that name their language, like Markdown code
+    /// fences, are checked with the grammar of that language.
+    pub check_code_blocks: bool,
+    /// The number of suggestions to show for each unknown word.
+    pub suggestions: usize,
+    /// How close, from 0 to 1, the best suggestion for a word must be for
+    /// `--fix` to apply it, see `fix::edit`.
+    #[serde(deserialize_with = "deserialize_fix_confidence")]
+    pub fix_confidence: f64,
+    pub format: OutputFormat,
+    /// The severity of each rule, by rule name. Rules not listed are errors.
+    pub severity: HashMap<Rule, Severity>,
+    /// The number of warnings above which a run fails.
+    pub max_warnings: Option<usize>,
+    /// Kinds of nodes not to check.
+    pub skip: Vec<NodeCategory>,
+    /// A directory of tree-sitter queries, see `QuerySources`.
+    pub queries: Option<PathBuf>,
+    pub replace_queries: bool,
+    /// Options for a single language, by language name.
+    pub languages: HashMap<String, LanguageConfig>,
+    #[serde(skip)]
+    pub root: PathBuf,
+    /// Problems with the configuration that did not prevent loading it.
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            words: Vec::new(),
+            ignore_words: Vec::new(),
+            flag_words: Vec::new(),
+            ignore_paths: Vec::new(),
+            ignore_patterns: vec!["Urls".to_string()],
+            dictionaries: vec!["dictionaries/*".to_string()],
+            word_patterns: Vec::new(),
+            layers: Vec::new(),
+            user_dictionaries: None,
+            min_word_length: 3,
+            case_sensitivity: CaseSensitivity::default(),
+            compound_words: Vec::new(),
+            compound_min_part_length: 2,
+            compound_max_parts: 3,
+            check_code_blocks: false,
+            suggestions: 3,
+            fix_confidence: 0.75,
+            format: OutputFormat::default(),
+            severity: HashMap::new(),
+            max_warnings: None,
+            skip: Vec::new(),
+            queries: None,
+            replace_queries: false,
+            languages: HashMap::new(),
+            root: PathBuf::from("."),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct LanguageConfig {
+    /// Whether files of this language are checked at all.
+    pub enabled: bool,
+    /// Kinds of nodes not to check, instead of the top-level `skip`.
+    pub skip: Option<Vec<NodeCategory>>,
+    /// Kinds of nodes to check for compound words, instead of the top-level
+    /// `compound-words`.
+    pub compound_words: Option<Vec<NodeCategory>>,
+    /// Globs of dictionaries for this language only, added to the base.
+    pub dictionaries: Vec<String>,
+    /// Patterns of text not to check, added to the top-level ones.
+    pub ignore_patterns: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct LayerConfig {
+    /// Shown when reporting which layer decided on a word.
+    pub name: String,
+    pub dictionaries: Vec<String>,
+    pub words: Vec<String>,
+    pub word_patterns: Vec<String>,
+    /// Whether the words of this layer are rejected rather than accepted.
+    pub forbidden: bool,
+}
+
+impl Default for LanguageConfig {
+    fn default() -> Self {
+        LanguageConfig {
+            enabled: true,
+            skip: None,
+            compound_words: None,
+            dictionaries: Vec::new(),
+            ignore_patterns: Vec::new(),
+        }
+    }
+}
+
+/// `value` if it is a valid fix confidence, from 0 to 1.
+fn check_fix_confidence(value: f64) -> Result<f64, String> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(value)
+    } else {
+        Err(format!("{value} is not a confidence from 0 to 1"))
+    }
+}
+
+/// Parses `--fix-confidence`.
+pub fn parse_fix_confidence(text: &str) -> Result<f64, String> {
+    let value = text.parse::<f64>().map_err(|error| error.to_string())?;
+
+    check_fix_confidence(value)
+}
+
+fn deserialize_fix_confidence<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    check_fix_confidence(f64::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+impl Config {
+    /// Loads the configuration file at `path`, as a cspell configuration if
+    /// it is a JSON file.
+    pub fn load(path: &Path) -> Result<Config> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read config {}", path.display()))?;
+
+        let mut config = if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            cspell::parse(&content)
+        } else {
+            toml::from_str(&content).map_err(anyhow::Error::from)
+        }
+        .with_context(|| format!("Invalid config {}", path.display()))?;
+
+        config.root = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+        config.resolve_paths();
+
+        Ok(config)
+    }
+
+    /// Joins the relative paths of the configuration to `root`, so they do
+    /// not depend on the directory rspell runs in. Paths given on the command
+    /// line stay relative to that directory.
+    fn resolve_paths(&mut self) {
+        let root = self.root.clone();
+        let resolve = |globs: &mut Vec<String>| {
+            for glob in globs {
+                *glob = root.join(&*glob).to_string_lossy().into_owned();
+            }
+        };
+
+        resolve(&mut self.dictionaries);
+        if let Some(globs) = &mut self.user_dictionaries {
+            resolve(globs);
+        }
+        for language in self.languages.values_mut() {
+            resolve(&mut language.dictionaries);
+        }
+        for layer in &mut self.layers {
+            resolve(&mut layer.dictionaries);
+        }
+        if let Some(queries) = &mut self.queries {
+            *queries = root.join(&*queries);
+        }
+    }
+
+    /// Looks for a configuration file in the current directory and each of
+    /// its parents, falling back to the defaults if there is none.
+    pub fn discover() -> Result<Config> {
+        let current_dir = env::current_dir().context("Could not get current directory")?;
+
+        for dir in current_dir.ancestors() {
+            for name in CONFIG_FILE_NAMES {
+                let path = dir.join(name);
+
+                if path.is_file() {
+                    return Config::load(&path);
+                }
+            }
+        }
+
+        Ok(Config::default())
+    }
+
+    pub fn language(&self, name: &str) -> Option<&LanguageConfig> {
+        self.languages.get(name)
+    }
+
+    /// Whether files of the language `name` should be checked.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.language(name).is_none_or(|language| language.enabled)
+    }
+
+    /// The categories of nodes to check for the language `name`.
+    pub fn categories(&self, name: &str) -> Vec<NodeCategory> {
+        let skip = self
+            .language(name)
+            .and_then(|language| language.skip.as_ref())
+            .unwrap_or(&self.skip);
+
+        NodeCategory::ALL
+            .into_iter()
+            .filter(|category| !skip.contains(category))
+            .collect()
+    }
+
+    /// The categories of nodes to check for compound words in the language
+    /// `name`.
+    pub fn compound_categories(&self, name: &str) -> Vec<NodeCategory> {
+        self.language(name)
+            .and_then(|language| language.compound_words.clone())
+            .unwrap_or_else(|| self.compound_words.clone())
+    }
+
+    /// The compound word settings.
+    pub fn compound_options(&self) -> CompoundOptions {
+        CompoundOptions {
+            min_part_length: self.compound_min_part_length,
+            max_parts: self.compound_max_parts,
+        }
+    }
+
+    /// The patterns of text not to check in the language `name`.
+    pub fn ignore_patterns(&self, name: &str) -> Vec<String> {
+        let mut patterns = self.ignore_patterns.clone();
+
+        if let Some(language) = self.language(name) {
+            patterns.extend(language.ignore_patterns.iter().cloned());
+        }
+
+        patterns
+    }
+
+    /// Whether `path` matches one of `ignore-paths`. Patterns are matched
+    /// against the path relative to the directory of the configuration file.
+    pub fn is_ignored(&self, path: &Path) -> Result<bool> {
+        let root = path::absolute(&self.root).context("Could not resolve config root")?;
+        let path = path::absolute(path).context("Could not resolve path")?;
+        let relative = path.strip_prefix(&root).unwrap_or(&path);
+
+        for pattern in &self.ignore_paths {
+            let pattern = glob::Pattern::new(pattern)
+                .with_context(|| format!("Invalid ignore path {pattern:?}"))?;
+
+            if pattern.matches_path(relative) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// The globs of the dictionaries to load for the language `name` only.
+    pub fn language_dictionary_globs(&self, name: &str) -> Vec<String> {
+        self.language(name)
+            .map(|language| language.dictionaries.clone())
+            .unwrap_or_default()
+    }
+
+    /// The globs of the user's dictionaries, see `user-dictionaries`. The
+    /// default is left out if the directory does not exist, as most users
+    /// have none.
+    pub fn user_dictionary_globs(&self) -> Vec<String> {
+        match &self.user_dictionaries {
+            Some(globs) => globs.clone(),
+            None => user_config_dir()
+                .map(|dir| dir.join("dictionaries"))
+                .filter(|dir| dir.is_dir())
+                .map(|dir| dir.join("*").to_string_lossy().into_owned())
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+/// rspell's directory in the user's configuration directory, following the
+/// XDG base directory specification.
+fn user_config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .map(|dir| dir.join("rspell"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(content: &str) -> Config {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn defaults_match_previous_behaviour() {
+        let config = parse("");
+
+        assert_eq!(config.dictionaries, ["dictionaries/*"]);
+        assert_eq!(config.min_word_length, 3);
+        assert_eq!(config.categories("typescript"), NodeCategory::ALL);
+    }
+
+    #[test]
+    fn reads_settings() {
+        let config = parse(
+            r#"
+            words = ["rspell"]
+            ignore-paths = ["target/**"]
+            min-word-length = 4
+            skip = ["string"]
+
+            [languages.markdown]
+            enabled = false
+
+            [languages.rust]
+            skip = []
+            "#,
+        );
+
+        assert_eq!(config.words, ["rspell"]);
+        assert_eq!(config.min_word_length, 4);
+        assert!(!config.is_enabled("markdown"));
+        assert!(config.is_enabled("typescript"));
+        assert_eq!(
+            config.categories("typescript"),
+            [
+                NodeCategory::Identifier,
+                NodeCategory::Comment,
+                NodeCategory::Text
+            ]
+        );
+        assert_eq!(config.categories("rust"), NodeCategory::ALL);
+        assert!(config.is_ignored(Path::new("target/debug/a.rs")).unwrap());
+        assert!(!config.is_ignored(Path::new("src/main.rs")).unwrap());
+    }
+
+    #[test]
+    fn reads_dictionary_layers() {
+        let config = parse(
+            r#"
+            user-dictionaries = ["me.txt"]
+
+            [languages.rust]
+            dictionaries = ["rust.txt"]
+
+            [[layers]]
+            name = "banned"
+            words = ["whitelist"]
+            forbidden = true
+            "#,
+        );
+
+        assert_eq!(config.dictionaries, ["dictionaries/*"]);
+        assert_eq!(config.language_dictionary_globs("rust"), ["rust.txt"]);
+        assert!(config.language_dictionary_globs("typescript").is_empty());
+        assert_eq!(config.user_dictionary_globs(), ["me.txt"]);
+        assert_eq!(config.layers[0].name, "banned");
+        assert!(config.layers[0].forbidden);
+    }
+
+    #[test]
+    fn reads_compound_word_settings() {
+        let config = parse(
+            r#"
+            compound-words = ["identifier"]
+            compound-max-parts = 2
+
+            [languages.c]
+            compound-words = ["identifier", "comment"]
+            "#,
+        );
+
+        assert_eq!(
+            config.compound_categories("typescript"),
+            [NodeCategory::Identifier]
+        );
+        assert_eq!(
+            config.compound_categories("c"),
+            [NodeCategory::Identifier, NodeCategory::Comment]
+        );
+        assert_eq!(
+            config.compound_options(),
+            CompoundOptions {
+                min_part_length: 2,
+                max_parts: 2
+            }
+        );
+    }
+
+    #[test]
+    fn adds_language_ignore_patterns_to_the_defaults() {
+        let config = parse(
+            r#"
+            [languages.css]
+            ignore-patterns = ["Hex"]
+            "#,
+        );
+
+        assert_eq!(config.ignore_patterns("typescript"), ["Urls"]);
+        assert_eq!(config.ignore_patterns("css"), ["Urls", "Hex"]);
+    }
+
+    #[test]
+    fn reads_rule_severities() {
+        let config = parse(
+            r#"
+            max-warnings = 10
+
+            [severity]
+            unknown-word = "warning"
+            casing = "off"
+            "#,
+        );
+
+        assert_eq!(
+            config.severity,
+            HashMap::from([
+                (Rule::UnknownWord, Severity::Warning),
+                (Rule::Casing, Severity::Off)
+            ])
+        );
+        assert_eq!(config.max_warnings, Some(10));
+        assert!(toml::from_str::<Config>("[severity]\ntypo = \"off\"").is_err());
+        assert!(toml::from_str::<Config>("[severity]\ncasing = \"fatal\"").is_err());
+    }
+
+    #[test]
+    fn resolves_paths_against_the_config_file() {
+        let dir = env::temp_dir().join(format!("rspell-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rspell.toml");
+        fs::write(
+            &path,
+            r#"
+            dictionaries = ["words/*", "/usr/share/dict/words"]
+            queries = "queries"
+
+            [languages.rust]
+            dictionaries = ["rust.txt"]
+            "#,
+        )
+        .unwrap();
+
+        let config = Config::load(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            config.dictionaries,
+            [
+                dir.join("words/*").to_string_lossy(),
+                "/usr/share/dict/words".into()
+            ]
+        );
+        assert_eq!(config.queries, Some(dir.join("queries")));
+        assert_eq!(
+            config.language_dictionary_globs("rust"),
+            [dir.join("rust.txt").to_string_lossy()]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(toml::from_str::<Config>("wrods = []").is_err());
+    }
+
+    #[test]
+    fn rejects_fix_confidences_out_of_range() {
+        assert_eq!(
+            toml::from_str::<Config>("fix-confidence = 0.5")
+                .unwrap()
+                .fix_confidence,
+            0.5
+        );
+        assert!(toml::from_str::<Config>("fix-confidence = 1.5").is_err());
+        assert!(toml::from_str::<Config>("fix-confidence = -0.1").is_err());
+
+        assert_eq!(parse_fix_confidence("1"), Ok(1.0));
+        assert!(parse_fix_confidence("75").is_err());
+        assert!(parse_fix_confidence("NaN").is_err());
+    }
+}
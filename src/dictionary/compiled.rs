@@ -0,0 +1,256 @@
+//! A precompiled dictionary: a finite state transducer holding the sorted
+//! set of words, behind a small versioned header. It is memory-mapped rather
+//! than read, so loading is instant and lookups do not allocate.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result, bail};
+use fst::{IntoStreamer, Set, SetBuilder, Streamer};
+use memmap2::Mmap;
+
+use super::Dictionary;
+use crate::suggest::{DamerauLevenshtein, damerau_levenshtein};
+
+/// The extension compiled dictionaries are recognised by.
+pub const EXTENSION: &str = "rsdict";
+
+const MAGIC: &[u8; 8] = b"RSPELLD\0";
+
+/// Bumped whenever the layout of the file changes.
+const VERSION: u32 = 1;
+
+/// Magic, version, flags and word count.
+const HEADER_LENGTH: usize = 8 + 4 + 4 + 8;
+
+/// Set in the header flags if words were lowercased when compiling.
+const FLAG_CASE_FOLDED: u32 = 1;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CompileOptions {
+    /// Lowercase every word, as `extract_tokens` does.
+    pub fold_case: bool,
+    /// Drop repeated words. The set itself cannot hold duplicates, so without
+    /// this, compiling fails on the first one.
+    pub dedup: bool,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        CompileOptions {
+            fold_case: true,
+            dedup: true,
+        }
+    }
+}
+
+/// Writes `words` as a compiled dictionary to `output`, returning the number
+/// of words written.
+pub fn compile(
+    words: impl IntoIterator<Item = String>,
+    output: &Path,
+    options: CompileOptions,
+) -> Result<usize> {
+    let mut words = words
+        .into_iter()
+        .map(|word| {
+            if options.fold_case {
+                word.to_lowercase()
+            } else {
+                word
+            }
+        })
+        .collect::<Vec<_>>();
+
+    words.sort_unstable();
+
+    if options.dedup {
+        words.dedup();
+    } else if let Some(pair) = words.windows(2).find(|pair| pair[0] == pair[1]) {
+        bail!("Duplicate word {:?}", pair[0]);
+    }
+
+    let file =
+        File::create(output).with_context(|| format!("Could not create {}", output.display()))?;
+    let mut writer = BufWriter::new(file);
+
+    let flags = if options.fold_case {
+        FLAG_CASE_FOLDED
+    } else {
+        0
+    };
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&flags.to_le_bytes())?;
+    writer.write_all(&(words.len() as u64).to_le_bytes())?;
+
+    let mut builder = SetBuilder::new(writer).context("Could not build dictionary")?;
+    builder
+        .extend_iter(&words)
+        .context("Could not build dictionary")?;
+    builder
+        .into_inner()
+        .context("Could not build dictionary")?
+        .flush()?;
+
+    Ok(words.len())
+}
+
+/// The part of a mapped file after the header.
+struct Body(Mmap);
+
+impl AsRef<[u8]> for Body {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[HEADER_LENGTH..]
+    }
+}
+
+pub struct CompiledDictionary {
+    source: String,
+    set: Set<Body>,
+    case_folded: bool,
+}
+
+impl CompiledDictionary {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).context("Could not open dictionary")?;
+
+        // SAFETY: The file is only read, and compiled dictionaries are not
+        // expected to be modified while rspell runs.
+        let mmap = unsafe { Mmap::map(&file) }.context("Could not map dictionary")?;
+
+        if mmap.len() < HEADER_LENGTH || &mmap[..8] != MAGIC {
+            bail!("Not a compiled dictionary");
+        }
+
+        let version = u32::from_le_bytes(mmap[8..12].try_into().unwrap());
+        if version != VERSION {
+            bail!(
+                "Compiled with format version {version}, but version {VERSION} is supported, compile it again"
+            );
+        }
+
+        let flags = u32::from_le_bytes(mmap[12..16].try_into().unwrap());
+
+        Ok(CompiledDictionary {
+            source: path.display().to_string(),
+            set: Set::new(Body(mmap)).context("Corrupt dictionary")?,
+            case_folded: flags & FLAG_CASE_FOLDED != 0,
+        })
+    }
+
+    pub fn is_case_folded(&self) -> bool {
+        self.case_folded
+    }
+
+    /// Every word in the dictionary, in order.
+    pub fn words(&self) -> Vec<String> {
+        let mut words = Vec::with_capacity(self.len());
+        let mut stream = self.set.into_stream();
+
+        while let Some(word) = stream.next() {
+            words.push(String::from_utf8_lossy(word).into_owned());
+        }
+
+        words
+    }
+}
+
+impl Dictionary for CompiledDictionary {
+    fn contains(&self, word: &str) -> bool {
+        self.set.contains(word)
+    }
+
+    /// Searches the mapped set directly, so only the words close to `word`
+    /// are ever copied out of it.
+    fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let chars = word.chars().collect::<Vec<_>>();
+        let mut stream = self.set.search(DamerauLevenshtein::new(word)).into_stream();
+        let mut suggestions = Vec::new();
+
+        while let Some(candidate) = stream.next() {
+            let candidate = String::from_utf8_lossy(candidate);
+
+            if candidate != word {
+                let distance = damerau_levenshtein(&chars, &candidate.chars().collect::<Vec<_>>());
+                suggestions.push((distance, candidate.into_owned()));
+            }
+        }
+
+        suggestions.sort();
+        suggestions
+            .into_iter()
+            .take(limit)
+            .map(|(_, suggestion)| suggestion)
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use super::*;
+
+    fn temporary_path(name: &str) -> std::path::PathBuf {
+        env::temp_dir().join(format!("rspell-{}-{name}.{EXTENSION}", std::process::id()))
+    }
+
+    #[test]
+    fn round_trips_words() {
+        let path = temporary_path("round-trip");
+        let words = ["World", "hello", "world", "GitHub"].map(String::from);
+
+        assert_eq!(compile(words, &path, CompileOptions::default()).unwrap(), 3);
+
+        let dictionary = CompiledDictionary::open(&path).unwrap();
+        assert!(dictionary.is_case_folded());
+        assert!(dictionary.contains("github"));
+        assert!(!dictionary.contains("GitHub"));
+        assert_eq!(dictionary.words(), ["github", "hello", "world"]);
+        assert_eq!(dictionary.suggest("wrold", 3), ["world"]);
+        assert_eq!(dictionary.suggest("helo", 3), ["hello"]);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn keeps_case_and_rejects_duplicates_if_asked() {
+        let path = temporary_path("options");
+        let options = CompileOptions {
+            fold_case: false,
+            dedup: false,
+        };
+
+        compile(["GitHub", "github"].map(String::from), &path, options).unwrap();
+        let dictionary = CompiledDictionary::open(&path).unwrap();
+        assert!(!dictionary.is_case_folded());
+        assert!(dictionary.contains("GitHub"));
+
+        assert!(compile(["a", "a"].map(String::from), &path, options).is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let path = temporary_path("invalid");
+        std::fs::write(&path, "hello\nworld\n").unwrap();
+
+        assert!(CompiledDictionary::open(&path).is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+//! Compound words written without separators, like "readfile" or "typeof",
+//! which are accepted if they split into dictionary words.
+
+use super::Dictionary;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompoundOptions {
+    /// The fewest characters a part can have, as single letters like "a"
+    /// would let almost anything split. Two keeps short words like "of" in
+    /// "typeof".
+    pub min_part_length: usize,
+    /// The most parts a word can split into.
+    pub max_parts: usize,
+}
+
+impl Default for CompoundOptions {
+    fn default() -> Self {
+        CompoundOptions {
+            min_part_length: 2,
+            max_parts: 3,
+        }
+    }
+}
+
+/// Splits `word` into two or more words of `dictionary`, preferring longer
+/// leading parts. Returns `None` if there is no such split.
+pub fn split_compound<'a, D: Dictionary + ?Sized>(
+    dictionary: &D,
+    word: &'a str,
+    options: CompoundOptions,
+) -> Option<Vec<&'a str>> {
+    if options.max_parts < 2 {
+        return None;
+    }
+
+    let mut parts = Vec::new();
+
+    split_into(dictionary, word, options, &mut parts).then_some(parts)
+}
+
+/// Fills `parts` with a split of `rest` into at most the remaining number of
+/// parts, backtracking on failure.
+fn split_into<'a, D: Dictionary + ?Sized>(
+    dictionary: &D,
+    rest: &'a str,
+    options: CompoundOptions,
+    parts: &mut Vec<&'a str>,
+) -> bool {
+    let length = rest.chars().count();
+
+    // The whole of `rest` as the last part, unless it would be the only one.
+    if !parts.is_empty() && length >= options.min_part_length && dictionary.contains(rest) {
+        parts.push(rest);
+        return true;
+    }
+
+    if parts.len() + 2 > options.max_parts || length < options.min_part_length * 2 {
+        return false;
+    }
+
+    let boundaries = rest
+        .char_indices()
+        .map(|(index, _)| index)
+        .skip(options.min_part_length)
+        .take(length + 1 - options.min_part_length * 2)
+        .collect::<Vec<_>>();
+
+    for &boundary in boundaries.iter().rev() {
+        let (head, tail) = rest.split_at(boundary);
+
+        if dictionary.contains(head) {
+            parts.push(head);
+
+            if split_into(dictionary, tail, options, parts) {
+                return true;
+            }
+
+            parts.pop();
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dictionary::word_list::WordList;
+
+    fn split(word: &str, options: CompoundOptions) -> Option<Vec<&str>> {
+        let dictionary = WordList::new(
+            "test",
+            [
+                "read", "file", "type", "of", "date", "time", "key", "value", "stamp", "a",
+            ]
+            .map(String::from),
+        );
+
+        split_compound(&dictionary, word, options)
+    }
+
+    #[test]
+    fn splits_into_dictionary_words() {
+        let options = CompoundOptions::default();
+
+        assert_eq!(split("readfile", options), Some(vec!["read", "file"]));
+        assert_eq!(split("keyvalue", options), Some(vec!["key", "value"]));
+        assert_eq!(
+            split("datetimestamp", options),
+            Some(vec!["date", "time", "stamp"])
+        );
+        assert_eq!(split("typeof", options), Some(vec!["type", "of"]));
+        assert_eq!(split("readfiel", options), None);
+        // Single letters are too short to be parts.
+        assert_eq!(split("areadfile", options), None);
+        assert_eq!(split("file", options), None);
+    }
+
+    #[test]
+    fn limits_parts() {
+        let options = CompoundOptions {
+            min_part_length: 3,
+            max_parts: 2,
+        };
+
+        assert_eq!(split("datetimestamp", options), None);
+
+        let options = CompoundOptions {
+            min_part_length: 4,
+            max_parts: 3,
+        };
+
+        assert_eq!(split("keyvalue", options), None);
+        assert_eq!(split("readfile", options), Some(vec!["read", "file"]));
+    }
+}
@@ -0,0 +1,145 @@
+//! Words that are reported even when spelled correctly, like deprecated
+//! product names or common confusables, each with an optional replacement
+//! and message.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::Dictionary;
+
+/// Why a word is flagged, and what to use instead.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Flag {
+    pub replacement: Option<String>,
+    pub message: Option<String>,
+}
+
+/// A flagged word as written in the configuration: either `"word"` or
+/// `"word -> replacement"`, or a table which can also hold a message.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum FlagWordConfig {
+    Short(String),
+    #[serde(rename_all = "kebab-case")]
+    Full {
+        word: String,
+        replacement: Option<String>,
+        message: Option<String>,
+    },
+}
+
+impl FlagWordConfig {
+    fn into_entry(self) -> (String, Flag) {
+        match self {
+            FlagWordConfig::Short(entry) => match entry.split_once("->") {
+                Some((word, replacement)) => (
+                    word.trim().to_lowercase(),
+                    Flag {
+                        replacement: Some(replacement.trim().to_string()),
+                        message: None,
+                    },
+                ),
+                None => (entry.trim().to_lowercase(), Flag::default()),
+            },
+            FlagWordConfig::Full {
+                word,
+                replacement,
+                message,
+            } => (
+                word.trim().to_lowercase(),
+                Flag {
+                    replacement,
+                    message,
+                },
+            ),
+        }
+    }
+}
+
+pub struct FlagWords {
+    source: String,
+    words: HashMap<String, Flag>,
+}
+
+impl FlagWords {
+    pub fn new(
+        source: impl Into<String>,
+        entries: impl IntoIterator<Item = FlagWordConfig>,
+    ) -> Self {
+        FlagWords {
+            source: source.into(),
+            words: entries
+                .into_iter()
+                .map(FlagWordConfig::into_entry)
+                .collect(),
+        }
+    }
+}
+
+impl Dictionary for FlagWords {
+    fn contains(&self, word: &str) -> bool {
+        self.words.contains_key(word)
+    }
+
+    /// The replacement of `word`, as flagged words are not suggestions for
+    /// anything.
+    fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        self.words
+            .get(word)
+            .and_then(|flag| flag.replacement.clone())
+            .into_iter()
+            .take(limit)
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn flag(&self, word: &str) -> Option<&Flag> {
+        self.words.get(word)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reads_replacements_and_messages() {
+        let flag_words: Vec<FlagWordConfig> = toml::from_str::<HashMap<String, _>>(
+            r#"
+            flag-words = [
+                "teh",
+                "Blacklist -> denylist",
+                { word = "master", replacement = "main", message = "Use inclusive language" },
+            ]
+            "#,
+        )
+        .unwrap()
+        .remove("flag-words")
+        .unwrap();
+
+        let flag_words = FlagWords::new("flag-words", flag_words);
+
+        assert_eq!(flag_words.flag("teh"), Some(&Flag::default()));
+        assert_eq!(
+            flag_words.flag("blacklist").unwrap().replacement.as_deref(),
+            Some("denylist")
+        );
+        assert_eq!(
+            flag_words.flag("master"),
+            Some(&Flag {
+                replacement: Some("main".to_string()),
+                message: Some("Use inclusive language".to_string()),
+            })
+        );
+        assert_eq!(flag_words.suggest("master", 3), ["main"]);
+        assert!(!flag_words.contains("main"));
+    }
+}
@@ -0,0 +1,572 @@
+//! Hunspell dictionaries: a `.dic` file of stems with affix flags, and an
+//! `.aff` file with the prefix and suffix rules those flags refer to. The
+//! rules are applied up front, expanding every stem into all of its forms.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{Context, Result, bail};
+
+use super::{Dictionary, word_list::WordList};
+
+/// An affix flag, with one or two characters or a number packed into a
+/// single value depending on the `FLAG` type.
+type Flag = u32;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum FlagType {
+    /// A single ASCII character per flag.
+    #[default]
+    Short,
+    /// Two ASCII characters per flag.
+    Long,
+    /// Comma separated decimal numbers.
+    Numeric,
+    /// A single Unicode character per flag.
+    Utf8,
+}
+
+impl FlagType {
+    fn parse(self, flags: &str) -> Result<Vec<Flag>> {
+        match self {
+            FlagType::Short | FlagType::Utf8 => Ok(flags.chars().map(Flag::from).collect()),
+            FlagType::Long => {
+                let chars = flags.chars().collect::<Vec<_>>();
+
+                if chars.len() % 2 != 0 {
+                    bail!("Odd number of characters in long flags {flags:?}");
+                }
+
+                Ok(chars
+                    .chunks(2)
+                    .map(|pair| (Flag::from(pair[0]) << 16) | Flag::from(pair[1]))
+                    .collect())
+            }
+            FlagType::Numeric => flags
+                .split(',')
+                .map(|flag| {
+                    flag.trim()
+                        .parse()
+                        .with_context(|| format!("Invalid numeric flag {flag:?}"))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A single character of an affix condition.
+#[derive(Debug, PartialEq, Eq)]
+enum CharClass {
+    Any,
+    Char(char),
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl CharClass {
+    fn matches(&self, char: char) -> bool {
+        match self {
+            CharClass::Any => true,
+            CharClass::Char(expected) => char == *expected,
+            CharClass::OneOf(chars) => chars.contains(&char),
+            CharClass::NoneOf(chars) => !chars.contains(&char),
+        }
+    }
+}
+
+/// The condition a word must meet for an affix to apply, a restricted
+/// regular expression of characters, `.` and `[...]`/`[^...]` classes.
+#[derive(Debug, PartialEq, Eq)]
+struct Condition(Vec<CharClass>);
+
+impl Condition {
+    fn parse(condition: &str) -> Result<Self> {
+        let mut classes = Vec::new();
+        let mut chars = condition.chars();
+
+        while let Some(char) = chars.next() {
+            classes.push(match char {
+                '.' => CharClass::Any,
+                '[' => {
+                    let mut set = Vec::new();
+                    let mut negated = false;
+
+                    loop {
+                        match chars.next() {
+                            Some('^') if set.is_empty() && !negated => negated = true,
+                            Some(']') => break,
+                            Some(char) => set.push(char),
+                            None => bail!("Unterminated condition {condition:?}"),
+                        }
+                    }
+
+                    if negated {
+                        CharClass::NoneOf(set)
+                    } else {
+                        CharClass::OneOf(set)
+                    }
+                }
+                char => CharClass::Char(char),
+            });
+        }
+
+        Ok(Condition(classes))
+    }
+
+    fn matches_start(&self, word: &[char]) -> bool {
+        word.len() >= self.0.len()
+            && self
+                .0
+                .iter()
+                .zip(word)
+                .all(|(class, char)| class.matches(*char))
+    }
+
+    fn matches_end(&self, word: &[char]) -> bool {
+        word.len() >= self.0.len()
+            && self
+                .0
+                .iter()
+                .rev()
+                .zip(word.iter().rev())
+                .all(|(class, char)| class.matches(*char))
+    }
+}
+
+#[derive(Debug)]
+struct AffixRule {
+    strip: Vec<char>,
+    add: Vec<char>,
+    /// Flags of further affixes that may be applied to the result.
+    continuation: Vec<Flag>,
+    condition: Condition,
+}
+
+#[derive(Debug)]
+struct AffixClass {
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+/// The rules of an `.aff` file that matter for expanding stems.
+#[derive(Debug, Default)]
+pub struct AffixFile {
+    flag_type: FlagType,
+    /// Flag sets referred to by number with `AF`, if the file uses aliases.
+    aliases: Vec<Vec<Flag>>,
+    prefixes: HashMap<Flag, AffixClass>,
+    suffixes: HashMap<Flag, AffixClass>,
+    /// Stems with this flag are only valid with an affix.
+    need_affix: Option<Flag>,
+    forbidden_word: Option<Flag>,
+}
+
+/// How deep suffixes are applied to each other through continuation flags.
+const MAX_SUFFIX_DEPTH: usize = 2;
+
+impl AffixFile {
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut affix_file = AffixFile::default();
+        let mut lines = content.lines().enumerate();
+        // The first `AF` line holds the number of aliases that follow.
+        let mut seen_alias_count = false;
+
+        while let Some((number, line)) = lines.next() {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+
+            let result = match fields.as_slice() {
+                ["FLAG", flag_type, ..] => {
+                    affix_file.flag_type = match *flag_type {
+                        "long" => FlagType::Long,
+                        "num" => FlagType::Numeric,
+                        "UTF-8" => FlagType::Utf8,
+                        other => bail!("Unknown FLAG type {other:?}"),
+                    };
+                    Ok(())
+                }
+                ["AF", _, ..] if !seen_alias_count => {
+                    seen_alias_count = true;
+                    Ok(())
+                }
+                ["AF", flags, ..] => affix_file
+                    .flag_type
+                    .parse(flags)
+                    .map(|flags| affix_file.aliases.push(flags)),
+                ["NEEDAFFIX", flag, ..] => affix_file
+                    .parse_flag(flag)
+                    .map(|flag| affix_file.need_affix = Some(flag)),
+                ["FORBIDDENWORD", flag, ..] => affix_file
+                    .parse_flag(flag)
+                    .map(|flag| affix_file.forbidden_word = Some(flag)),
+                [kind @ ("PFX" | "SFX"), flag, cross_product, count, ..] => {
+                    let count = count
+                        .parse::<usize>()
+                        .with_context(|| format!("Invalid rule count {count:?}"))?;
+
+                    let class = AffixClass {
+                        cross_product: *cross_product == "Y",
+                        rules: lines
+                            .by_ref()
+                            .take(count)
+                            .map(|(_, line)| affix_file.parse_rule(line))
+                            .collect::<Result<_>>()?,
+                    };
+
+                    let flag = affix_file.parse_flag(flag)?;
+                    if *kind == "PFX" {
+                        affix_file.prefixes.insert(flag, class);
+                    } else {
+                        affix_file.suffixes.insert(flag, class);
+                    }
+
+                    Ok(())
+                }
+                _ => Ok(()),
+            };
+
+            result.with_context(|| format!("Invalid affix file on line {}", number + 1))?;
+        }
+
+        Ok(affix_file)
+    }
+
+    fn parse_flag(&self, flag: &str) -> Result<Flag> {
+        match self.flag_type.parse(flag)?.as_slice() {
+            [flag] => Ok(*flag),
+            _ => bail!("Expected a single flag, got {flag:?}"),
+        }
+    }
+
+    /// Parses flags of a word or rule, which may be an `AF` alias number.
+    fn parse_flags(&self, flags: &str) -> Result<Vec<Flag>> {
+        if self.aliases.is_empty() {
+            return self.flag_type.parse(flags);
+        }
+
+        let alias = flags
+            .parse::<usize>()
+            .with_context(|| format!("Invalid flag alias {flags:?}"))?;
+
+        self.aliases
+            .get(alias.wrapping_sub(1))
+            .cloned()
+            .with_context(|| format!("Unknown flag alias {alias}"))
+    }
+
+    /// Parses a rule line of the form `SFX D 0 ed/FLAGS [^e]`.
+    fn parse_rule(&self, line: &str) -> Result<AffixRule> {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+
+        let [_, _, strip, add, rest @ ..] = fields.as_slice() else {
+            bail!("Invalid affix rule {line:?}");
+        };
+
+        let (add, continuation) = match add.split_once('/') {
+            Some((add, flags)) => (add, self.parse_flags(flags)?),
+            None => (*add, Vec::new()),
+        };
+
+        let affix = |affix: &str| match affix {
+            "0" => Vec::new(),
+            affix => affix.chars().collect(),
+        };
+
+        Ok(AffixRule {
+            strip: affix(strip),
+            add: affix(add),
+            continuation,
+            condition: Condition::parse(rest.first().copied().unwrap_or("."))?,
+        })
+    }
+
+    /// Every form of `stem` with the affixes of `flags` applied.
+    pub fn expand(&self, stem: &str, flags: &[Flag]) -> Vec<String> {
+        if self
+            .forbidden_word
+            .is_some_and(|flag| flags.contains(&flag))
+        {
+            return Vec::new();
+        }
+
+        let stem = stem.chars().collect::<Vec<_>>();
+        let mut forms = Vec::new();
+
+        if !self.need_affix.is_some_and(|flag| flags.contains(&flag)) {
+            forms.push(stem.clone());
+        }
+
+        // Suffixed forms, and whether they may also take a prefix.
+        let mut suffixed = Vec::new();
+        self.apply_suffixes(&stem, flags, true, MAX_SUFFIX_DEPTH, &mut suffixed);
+
+        for flag in flags {
+            let Some(class) = self.prefixes.get(flag) else {
+                continue;
+            };
+
+            for rule in &class.rules {
+                if let Some(form) = apply_prefix(rule, &stem) {
+                    forms.push(form);
+                }
+
+                if !class.cross_product {
+                    continue;
+                }
+
+                for (form, _) in suffixed.iter().filter(|(_, cross_product)| *cross_product) {
+                    if let Some(form) = apply_prefix(rule, form) {
+                        forms.push(form);
+                    }
+                }
+            }
+        }
+
+        forms.extend(suffixed.into_iter().map(|(form, _)| form));
+        forms.into_iter().map(String::from_iter).collect()
+    }
+
+    fn apply_suffixes(
+        &self,
+        word: &[char],
+        flags: &[Flag],
+        cross_product: bool,
+        depth: usize,
+        forms: &mut Vec<(Vec<char>, bool)>,
+    ) {
+        if depth == 0 {
+            return;
+        }
+
+        for flag in flags {
+            let Some(class) = self.suffixes.get(flag) else {
+                continue;
+            };
+
+            for rule in &class.rules {
+                if !rule.condition.matches_end(word) || !word.ends_with(&rule.strip) {
+                    continue;
+                }
+
+                let mut form = word[..word.len() - rule.strip.len()].to_vec();
+                form.extend(&rule.add);
+
+                let cross_product = cross_product && class.cross_product;
+                self.apply_suffixes(&form, &rule.continuation, cross_product, depth - 1, forms);
+                forms.push((form, cross_product));
+            }
+        }
+    }
+}
+
+fn apply_prefix(rule: &AffixRule, word: &[char]) -> Option<Vec<char>> {
+    if !rule.condition.matches_start(word) || !word.starts_with(&rule.strip) {
+        return None;
+    }
+
+    let mut form = rule.add.clone();
+    form.extend(&word[rule.strip.len()..]);
+    Some(form)
+}
+
+/// Expands every stem of the `.dic` file `dic` with the rules in `aff`.
+pub fn expand(aff: &AffixFile, dic: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+
+    // The first line is the approximate number of stems.
+    for (number, line) in dic.lines().enumerate().skip(1) {
+        // Morphological fields follow the word after whitespace.
+        let Some(entry) = line.split_whitespace().next() else {
+            continue;
+        };
+
+        let (stem, flags) = match entry.split_once('/') {
+            Some((stem, flags)) => (stem, aff.parse_flags(flags)),
+            None => (entry, Ok(Vec::new())),
+        };
+
+        let flags = flags.with_context(|| format!("Invalid dictionary on line {}", number + 1))?;
+
+        words.extend(aff.expand(stem, &flags));
+    }
+
+    Ok(words)
+}
+
+/// A Hunspell dictionary, held as the expanded forms of its stems.
+pub struct HunspellDictionary {
+    forms: WordList,
+}
+
+impl HunspellDictionary {
+    pub fn open(dic: &Path) -> Result<Self> {
+        Ok(HunspellDictionary {
+            forms: WordList::new(dic.display().to_string(), read(dic)?),
+        })
+    }
+}
+
+impl Dictionary for HunspellDictionary {
+    fn contains(&self, word: &str) -> bool {
+        self.forms.contains(word)
+    }
+
+    fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        self.forms.suggest(word, limit)
+    }
+
+    fn len(&self) -> usize {
+        self.forms.len()
+    }
+
+    fn source(&self) -> &str {
+        self.forms.source()
+    }
+
+    fn casings(&self, word: &str) -> &[String] {
+        self.forms.casings(word)
+    }
+}
+
+/// Expands the Hunspell dictionary `dic`, using the `.aff` file of the same
+/// name if there is one.
+pub fn read(dic: &Path) -> Result<Vec<String>> {
+    let aff = dic.with_extension("aff");
+
+    let (affix_file, encoding) = if aff.exists() {
+        let bytes = fs::read(&aff).context("Could not read affix file")?;
+        let encoding = encoding(&bytes);
+
+        (AffixFile::parse(&decode(&bytes, encoding))?, encoding)
+    } else {
+        (AffixFile::default(), Encoding::Utf8)
+    };
+
+    let dic = decode(
+        &fs::read(dic).context("Could not read dictionary")?,
+        encoding,
+    );
+
+    expand(&affix_file, &dic)
+}
+
+/// Decodes the content of a `.dic` or `.aff` file, which may use a legacy
+/// encoding declared with `SET` in the `.aff` file.
+pub fn decode(bytes: &[u8], encoding: Encoding) -> String {
+    match encoding {
+        Encoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+        Encoding::Latin1 => bytes.iter().map(|byte| char::from(*byte)).collect(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Latin1,
+}
+
+/// Reads the encoding from the `SET` line of an `.aff` file. Only the
+/// encodings used by common dictionaries are supported, and anything other
+/// than ISO 8859-1 is read as UTF-8.
+pub fn encoding(aff: &[u8]) -> Encoding {
+    let set = aff
+        .split(|byte| *byte == b'\n')
+        .find_map(|line| line.strip_prefix(b"SET "));
+
+    match set.map(|set| String::from_utf8_lossy(set).trim().to_uppercase()) {
+        Some(set) if set == "ISO8859-1" || set == "ISO-8859-1" => Encoding::Latin1,
+        _ => Encoding::Utf8,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const AFF: &str = "
+SET UTF-8
+NEEDAFFIX X
+
+PFX U Y 1
+PFX U 0 un .
+
+SFX D Y 2
+SFX D 0 d e
+SFX D 0 ed [^e]
+
+SFX G Y 2
+SFX G e ing e
+SFX G 0 ing [^e]
+
+SFX S Y 1
+SFX S 0 s .
+
+SFX E Y 1
+SFX E 0 es s
+
+SFX N N 1
+SFX N 0 ness/E .
+";
+
+    fn expand_dic(aff: &str, dic: &str) -> Vec<String> {
+        let mut words = expand(&AffixFile::parse(aff).unwrap(), dic).unwrap();
+        words.sort();
+        words
+    }
+
+    #[test]
+    fn expands_suffixes() {
+        assert_eq!(
+            expand_dic(AFF, "1\nparse/DGS\n"),
+            ["parse", "parsed", "parses", "parsing"]
+        );
+    }
+
+    #[test]
+    fn expands_prefixes_with_cross_product() {
+        assert_eq!(
+            expand_dic(AFF, "1\ndo/US\n"),
+            ["do", "dos", "undo", "undos"]
+        );
+    }
+
+    #[test]
+    fn applies_continuation_classes() {
+        assert_eq!(
+            expand_dic(AFF, "1\nkind/NU\n"),
+            ["kind", "kindness", "kindnesses", "unkind"]
+        );
+    }
+
+    #[test]
+    fn skips_stems_needing_an_affix() {
+        assert_eq!(expand_dic(AFF, "1\nfoo/XS\tpo:noun\n"), ["foos"]);
+    }
+
+    #[test]
+    fn supports_long_numeric_and_aliased_flags() {
+        let long = "FLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n";
+        assert_eq!(expand_dic(long, "1\ncat/Aa\n"), ["cat", "cats"]);
+
+        let numeric = "FLAG num\nSFX 12 Y 1\nSFX 12 0 s .\n";
+        assert_eq!(expand_dic(numeric, "1\ncat/12,7\n"), ["cat", "cats"]);
+
+        let aliased = "AF 1\nAF S\nSFX S Y 1\nSFX S 0 s .\n";
+        assert_eq!(expand_dic(aliased, "1\ncat/1\n"), ["cat", "cats"]);
+    }
+
+    #[test]
+    fn parses_conditions() {
+        let condition = Condition::parse("[^aeiou]y").unwrap();
+        let chars = |word: &str| word.chars().collect::<Vec<_>>();
+
+        assert!(condition.matches_end(&chars("fly")));
+        assert!(!condition.matches_end(&chars("day")));
+        assert!(!condition.matches_end(&chars("y")));
+    }
+
+    #[test]
+    fn decodes_latin1() {
+        let aff = b"SET ISO8859-1\n";
+        assert_eq!(encoding(aff), Encoding::Latin1);
+        assert_eq!(decode(b"gr\xfc\xdf", Encoding::Latin1), "grüß");
+    }
+}
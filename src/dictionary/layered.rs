@@ -0,0 +1,228 @@
+//! Dictionaries stacked in layers, e.g. a language's base dictionaries,
+//! then a framework's, the project's and the user's own. A later layer takes
+//! precedence over the ones before it, which matters for forbidden layers:
+//! the project can forbid a word of the base dictionaries, and the user can
+//! allow it again.
+
+use std::{collections::HashSet, sync::Arc};
+
+use super::{Dictionary, flag_words::Flag};
+use crate::suggest::damerau_levenshtein;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerKind {
+    /// Words in the layer are accepted.
+    Allow,
+    /// Words in the layer are rejected, even if an earlier layer accepts
+    /// them.
+    Forbid,
+}
+
+#[derive(Clone)]
+pub struct Layer {
+    pub name: String,
+    pub kind: LayerKind,
+    pub dictionaries: Vec<Arc<dyn Dictionary>>,
+}
+
+impl Layer {
+    pub fn allow(name: impl Into<String>, dictionaries: Vec<Arc<dyn Dictionary>>) -> Self {
+        Layer {
+            name: name.into(),
+            kind: LayerKind::Allow,
+            dictionaries,
+        }
+    }
+
+    pub fn forbid(name: impl Into<String>, dictionaries: Vec<Arc<dyn Dictionary>>) -> Self {
+        Layer {
+            name: name.into(),
+            kind: LayerKind::Forbid,
+            dictionaries,
+        }
+    }
+}
+
+/// The layer and dictionary that decided on a word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup<'a> {
+    Accepted {
+        layer: &'a str,
+        source: &'a str,
+        /// The casings the word must be written in, empty if any will do.
+        casings: &'a [String],
+    },
+    Forbidden {
+        layer: &'a str,
+        source: &'a str,
+        /// The replacement and message, if the word is a flagged word.
+        flag: Option<&'a Flag>,
+    },
+    Unknown,
+}
+
+#[derive(Default, Clone)]
+pub struct LayeredDictionary {
+    layers: Vec<Layer>,
+}
+
+impl LayeredDictionary {
+    /// Adds `layer` on top of the existing ones.
+    pub fn push(&mut self, layer: Layer) {
+        self.layers.push(layer);
+    }
+
+    /// Finds the topmost layer with a dictionary containing `word`. Within
+    /// a layer, a dictionary accepting any casing of the word is preferred.
+    pub fn lookup(&self, word: &str) -> Lookup<'_> {
+        for layer in self.layers.iter().rev() {
+            let Some(dictionary) = layer
+                .dictionaries
+                .iter()
+                .filter(|dictionary| dictionary.contains(word))
+                .min_by_key(|dictionary| !dictionary.casings(word).is_empty())
+            else {
+                continue;
+            };
+
+            let (name, source) = (layer.name.as_str(), dictionary.source());
+
+            return match layer.kind {
+                LayerKind::Allow => Lookup::Accepted {
+                    layer: name,
+                    source,
+                    casings: dictionary.casings(word),
+                },
+                LayerKind::Forbid => Lookup::Forbidden {
+                    layer: name,
+                    source,
+                    flag: dictionary.flag(word),
+                },
+            };
+        }
+
+        Lookup::Unknown
+    }
+}
+
+impl Dictionary for LayeredDictionary {
+    fn contains(&self, word: &str) -> bool {
+        matches!(self.lookup(word), Lookup::Accepted { .. })
+    }
+
+    /// Merges the suggestions of every allowing layer, leaving out words a
+    /// layer above forbids.
+    fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let chars = word.chars().collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+        let mut suggestions = Vec::new();
+
+        for layer in &self.layers {
+            if layer.kind == LayerKind::Forbid {
+                continue;
+            }
+
+            for dictionary in &layer.dictionaries {
+                for suggestion in dictionary.suggest(word, limit) {
+                    // Suggestions keep the casing a dictionary requires.
+                    let folded = suggestion.to_lowercase();
+
+                    if self.contains(&folded) && seen.insert(folded) {
+                        let distance =
+                            damerau_levenshtein(&chars, &suggestion.chars().collect::<Vec<_>>());
+                        suggestions.push((distance, suggestion));
+                    }
+                }
+            }
+        }
+
+        suggestions.sort();
+        suggestions
+            .into_iter()
+            .take(limit)
+            .map(|(_, suggestion)| suggestion)
+            .collect()
+    }
+
+    /// The number of words accepted by the allowing layers, counting words
+    /// in several dictionaries once for each.
+    fn len(&self) -> usize {
+        self.layers
+            .iter()
+            .filter(|layer| layer.kind == LayerKind::Allow)
+            .flat_map(|layer| &layer.dictionaries)
+            .map(|dictionary| dictionary.len())
+            .sum()
+    }
+
+    fn source(&self) -> &str {
+        "layered"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dictionary::word_list::WordList;
+
+    fn words(source: &str, words: &[&str]) -> Arc<dyn Dictionary> {
+        Arc::new(WordList::new(
+            source,
+            words.iter().map(|word| word.to_string()),
+        ))
+    }
+
+    fn stack() -> LayeredDictionary {
+        let mut dictionary = LayeredDictionary::default();
+        dictionary.push(Layer::allow(
+            "base",
+            vec![words("en.txt", &["hello", "world", "whitelist"])],
+        ));
+        dictionary.push(Layer::forbid(
+            "banned",
+            vec![words("banned.txt", &["whitelist", "hello"])],
+        ));
+        dictionary.push(Layer::allow("user", vec![words("user.txt", &["hello"])]));
+        dictionary
+    }
+
+    #[test]
+    fn reports_the_deciding_layer() {
+        let dictionary = stack();
+
+        assert_eq!(
+            dictionary.lookup("world"),
+            Lookup::Accepted {
+                layer: "base",
+                source: "en.txt",
+                casings: &[]
+            }
+        );
+        assert_eq!(
+            dictionary.lookup("whitelist"),
+            Lookup::Forbidden {
+                layer: "banned",
+                source: "banned.txt",
+                flag: None
+            }
+        );
+        assert_eq!(
+            dictionary.lookup("hello"),
+            Lookup::Accepted {
+                layer: "user",
+                source: "user.txt",
+                casings: &[]
+            }
+        );
+        assert_eq!(dictionary.lookup("wrlod"), Lookup::Unknown);
+    }
+
+    #[test]
+    fn does_not_suggest_forbidden_words() {
+        let dictionary = stack();
+
+        assert!(!dictionary.contains("whitelist"));
+        assert!(dictionary.suggest("whitelst", 3).is_empty());
+        assert_eq!(dictionary.suggest("helo", 3), ["hello"]);
+    }
+}
@@ -0,0 +1,111 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::{Context, Result};
+
+pub mod compiled;
+pub mod compound;
+pub mod flag_words;
+pub mod hunspell;
+pub mod layered;
+pub mod pattern;
+pub mod word_list;
+
+use compiled::CompiledDictionary;
+use flag_words::Flag;
+use hunspell::HunspellDictionary;
+use word_list::WordList;
+
+/// A set of accepted words. Words are looked up as `extract_tokens` produces
+/// them, lowercased, and entries written with capitals can require the
+/// original text to match their casing, see [`Dictionary::casings`].
+pub trait Dictionary: Send + Sync {
+    fn contains(&self, word: &str) -> bool;
+
+    /// Up to `limit` words close to `word`, best first.
+    fn suggest(&self, word: &str, limit: usize) -> Vec<String>;
+
+    /// The number of words, or of patterns for dictionaries matching them.
+    fn len(&self) -> usize;
+
+    /// Where the words came from, usually the path of a file.
+    fn source(&self) -> &str;
+
+    /// The ways `word` may be written, if the dictionary only has it with
+    /// capitals, like "GitHub". Empty if any casing is accepted.
+    fn casings(&self, _word: &str) -> &[String] {
+        &[]
+    }
+
+    /// Why `word` is flagged, for dictionaries of flagged words.
+    fn flag(&self, _word: &str) -> Option<&Flag> {
+        None
+    }
+}
+
+/// Opens a dictionary file. Hunspell `.dic` files are expanded with the affix
+/// rules of the `.aff` file next to them, compiled dictionaries are
+/// memory-mapped, and any other file is read as a list of words, one per
+/// line. `.aff` files are only read as part of their `.dic` file, so there is
+/// no dictionary for them.
+pub fn open(file: &Path) -> Result<Option<Arc<dyn Dictionary>>> {
+    let dictionary: Arc<dyn Dictionary> = match extension(file) {
+        Some("aff") => return Ok(None),
+        Some("dic") => Arc::new(HunspellDictionary::open(file)?),
+        Some(compiled::EXTENSION) => {
+            let dictionary = CompiledDictionary::open(file)?;
+
+            if !dictionary.is_case_folded() {
+                eprintln!(
+                    "warning: {} was compiled without case folding, only its lowercase words will match",
+                    file.display()
+                );
+            }
+
+            Arc::new(dictionary)
+        }
+        _ => Arc::new(WordList::open(file)?),
+    };
+
+    Ok(Some(dictionary))
+}
+
+/// Opens every file matching `glob_path`, see [`open`].
+pub fn load(glob_path: &str) -> Result<Vec<Arc<dyn Dictionary>>> {
+    let mut dictionaries = Vec::new();
+
+    for file in glob::glob(glob_path).context("Failed to glob dictionaries")? {
+        let file = file.context("Failed to read file")?;
+
+        dictionaries
+            .extend(open(&file).with_context(|| format!("Could not load {}", file.display()))?);
+    }
+
+    Ok(dictionaries)
+}
+
+/// Reads the words of every file matching `glob_path` as they are written,
+/// for compiling them into a single dictionary.
+pub fn read_words(glob_path: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+
+    for file in glob::glob(glob_path).context("Failed to glob dictionaries")? {
+        let file = file.context("Failed to read file")?;
+
+        let file_words = match extension(&file) {
+            Some("aff") => continue,
+            Some("dic") => hunspell::read(&file),
+            Some(compiled::EXTENSION) => {
+                CompiledDictionary::open(&file).map(|dictionary| dictionary.words())
+            }
+            _ => word_list::read(&file),
+        };
+
+        words.extend(file_words.with_context(|| format!("Could not load {}", file.display()))?);
+    }
+
+    Ok(words)
+}
+
+fn extension(file: &Path) -> Option<&str> {
+    file.extension().and_then(|extension| extension.to_str())
+}
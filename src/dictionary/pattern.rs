@@ -0,0 +1,76 @@
+//! A dictionary of regular expressions, for families of words that cannot
+//! be listed, like generated identifiers or version-like tokens.
+
+use anyhow::{Context, Result};
+use fancy_regex::Regex;
+
+use super::Dictionary;
+
+pub struct PatternDictionary {
+    source: String,
+    patterns: Vec<Regex>,
+}
+
+impl PatternDictionary {
+    /// Accepts the words matched in full by one of `patterns`.
+    pub fn new(source: impl Into<String>, patterns: &[String]) -> Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(&format!("^(?:{pattern})$"))
+                    .with_context(|| format!("Invalid word pattern {pattern:?}"))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(PatternDictionary {
+            source: source.into(),
+            patterns,
+        })
+    }
+}
+
+impl Dictionary for PatternDictionary {
+    fn contains(&self, word: &str) -> bool {
+        // A pattern that fails to match, e.g. by backtracking too far, does
+        // not accept the word.
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.is_match(word).unwrap_or(false))
+    }
+
+    /// Patterns describe words rather than list them, so there is nothing to
+    /// suggest.
+    fn suggest(&self, _word: &str, _limit: usize) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn accepts_whole_matches_only() {
+        let dictionary =
+            PatternDictionary::new("test", &["x[0-9a-f]+".to_string(), "v\\d+".to_string()])
+                .unwrap();
+
+        assert!(dictionary.contains("x1f"));
+        assert!(dictionary.contains("v2"));
+        assert!(!dictionary.contains("ax1f"));
+        assert!(!dictionary.contains("v2beta"));
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert!(PatternDictionary::new("test", &["(".to_string()]).is_err());
+    }
+}
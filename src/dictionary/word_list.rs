@@ -0,0 +1,148 @@
+//! A plain list of words held in memory, read from a text file with one word
+//! per line or given in the configuration.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    sync::OnceLock,
+};
+
+use anyhow::{Context, Result};
+
+use super::Dictionary;
+use crate::suggest::Suggester;
+
+pub struct WordList {
+    source: String,
+    words: HashSet<String>,
+    /// The entries of words only listed with capitals, by lowercased word.
+    casings: HashMap<String, Vec<String>>,
+    /// Whether the words may be suggested, which is not the case for words
+    /// that are only tolerated, like `ignore-words`.
+    suggestible: bool,
+    /// Built on the first call to `suggest`, as most runs never need it.
+    suggester: OnceLock<Suggester>,
+}
+
+impl WordList {
+    /// A list of `words`, lowercased as `extract_tokens` normalises them.
+    /// Words written with capitals, and not also in lowercase, keep their
+    /// casing as a requirement.
+    pub fn new(source: impl Into<String>, words: impl IntoIterator<Item = String>) -> Self {
+        let mut lowercase = HashSet::new();
+        let mut casings = HashMap::<String, Vec<String>>::new();
+
+        for word in words {
+            let folded = word.to_lowercase();
+
+            if folded == word {
+                lowercase.insert(folded);
+            } else {
+                casings.entry(folded).or_default().push(word);
+            }
+        }
+
+        casings.retain(|word, _| !lowercase.contains(word));
+        lowercase.extend(casings.keys().cloned());
+
+        WordList {
+            source: source.into(),
+            words: lowercase,
+            casings,
+            suggestible: true,
+            suggester: OnceLock::new(),
+        }
+    }
+
+    pub fn open(file: &Path) -> Result<Self> {
+        Ok(WordList::new(file.display().to_string(), read(file)?))
+    }
+
+    /// Accepts the words without ever suggesting them.
+    pub fn without_suggestions(mut self) -> Self {
+        self.suggestible = false;
+        self
+    }
+}
+
+impl Dictionary for WordList {
+    fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        if !self.suggestible {
+            return Vec::new();
+        }
+
+        self.suggester
+            .get_or_init(|| Suggester::new(&self.words))
+            .suggest(word, limit)
+            .into_iter()
+            .map(|suggestion| match self.casings.get(&suggestion) {
+                Some(casings) => casings[0].clone(),
+                None => suggestion,
+            })
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn casings(&self, word: &str) -> &[String] {
+        self.casings.get(word).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Reads the words of `file`, one per line, as they are written.
+pub fn read(file: &Path) -> Result<Vec<String>> {
+    Ok(fs::read_to_string(file)
+        .context("Could not read word list")?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn looks_up_and_suggests_words() {
+        let words = WordList::new("test", ["hello", "world"].map(String::from));
+
+        assert!(words.contains("hello"));
+        assert!(!words.contains("Hello"));
+        assert_eq!(words.suggest("helo", 3), ["hello"]);
+        assert_eq!(words.len(), 2);
+    }
+
+    #[test]
+    fn keeps_casings_of_words_only_listed_with_capitals() {
+        let words = WordList::new(
+            "test",
+            ["GitHub", "Polish", "polish", "JavaScript"].map(String::from),
+        );
+
+        assert!(words.contains("github"));
+        assert_eq!(words.casings("github"), ["GitHub"]);
+        assert!(words.casings("polish").is_empty());
+        assert_eq!(words.suggest("javascrpt", 3), ["JavaScript"]);
+    }
+
+    #[test]
+    fn never_suggests_tolerated_words() {
+        let words = WordList::new("ignore-words", ["hello".to_string()]).without_suggestions();
+
+        assert!(words.contains("hello"));
+        assert!(words.suggest("helo", 3).is_empty());
+    }
+}
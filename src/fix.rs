@@ -0,0 +1,392 @@
+//! Corrections for `--fix`: the edit for each reported word that has one
+//! clearly right correction, applied to the source of a file.
+
+use std::{ops::Range, path::Path};
+
+use anyhow::{Context, Result};
+use tree_sitter::Parser;
+
+use crate::{
+    dictionary::Dictionary,
+    parsing::{
+        language::LanguageDefinition,
+        parser::{Problem, UnknownWord},
+    },
+    suggest::damerau_levenshtein,
+};
+
+/// The lines of context around each change in a diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// A replacement of the bytes in `range` of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+/// The edit correcting `unknown` in `source`, if there is one clearly right
+/// correction. Forbidden words with a replacement and words with a single
+/// expected casing always have one. An unknown word has one if its closest
+/// suggestion is closer than any other, and its confidence, one minus the
+/// edit distance relative to one more than the length of the longer word, is
+/// at least `min_confidence`. The one more lets a single edit in a word of
+/// three letters, the shortest checked by default, reach the default 0.75.
+pub fn edit(
+    source: &str,
+    unknown: &UnknownWord,
+    dictionary: &dyn Dictionary,
+    min_confidence: f64,
+) -> Option<Edit> {
+    let span = unknown.word.span;
+    let original = &source[span.start..span.end];
+
+    let replacement = match &unknown.problem {
+        Problem::Forbidden(forbidden) => match_casing(original, forbidden.replacement.as_ref()?),
+        Problem::Casing { expected } => match expected.as_slice() {
+            [expected] => expected.clone(),
+            _ => return None,
+        },
+        Problem::Unknown => {
+            let word = unknown.word.text.chars().collect::<Vec<_>>();
+            let suggestions = dictionary
+                .suggest(&unknown.word.text, 2)
+                .into_iter()
+                .map(|suggestion| {
+                    let chars = suggestion.to_lowercase().chars().collect::<Vec<_>>();
+                    let distance = damerau_levenshtein(&word, &chars);
+
+                    (distance, word.len().max(chars.len()), suggestion)
+                })
+                .collect::<Vec<_>>();
+
+            let [(distance, length, best), rest @ ..] = suggestions.as_slice() else {
+                return None;
+            };
+
+            let confidence = 1.0 - *distance as f64 / (*length + 1) as f64;
+            if confidence < min_confidence || rest.iter().any(|(other, ..)| other <= distance) {
+                return None;
+            }
+
+            match_casing(original, best)
+        }
+    };
+
+    (replacement != original).then_some(Edit {
+        range: span.start..span.end,
+        replacement,
+    })
+}
+
+/// `replacement` written in the casing of `original`: lowercase,
+/// capitalized or uppercase. As words are the parts of identifiers, this
+/// keeps camelCase, PascalCase, snake_case and SCREAMING_SNAKE identifiers
+/// in their style. A replacement a dictionary requires capitals for, like
+/// "GitHub", keeps them unless the original is uppercase.
+pub fn match_casing(original: &str, replacement: &str) -> String {
+    let mut letters = original.chars().filter(|char| char.is_alphabetic());
+    let first_upper = letters.next().is_some_and(char::is_uppercase);
+    let rest_upper = letters.clone().all(char::is_uppercase);
+    let has_rest = letters.next().is_some();
+
+    if first_upper && has_rest && rest_upper {
+        replacement.to_uppercase()
+    } else if replacement.chars().any(char::is_uppercase) {
+        replacement.to_string()
+    } else if first_upper {
+        let mut chars = replacement.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    } else {
+        replacement.to_string()
+    }
+}
+
+/// `source` with `edits` applied. An edit overlapping an earlier one, like
+/// the same word reported twice, is dropped. They are applied from the last
+/// to the first, so the byte ranges of those still to apply stay valid.
+pub fn apply(source: &str, edits: &[Edit]) -> String {
+    let mut sorted = edits.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|edit| edit.range.start);
+
+    let mut kept = Vec::<&Edit>::with_capacity(sorted.len());
+    for edit in sorted {
+        if kept
+            .last()
+            .is_none_or(|last| last.range.end <= edit.range.start)
+        {
+            kept.push(edit);
+        }
+    }
+
+    let mut fixed = source.to_string();
+    for edit in kept.into_iter().rev() {
+        fixed.replace_range(edit.range.clone(), &edit.replacement);
+    }
+
+    fixed
+}
+
+/// Whether `source` parses without errors with the grammar of `language`.
+/// Plain text always does.
+pub fn parses(source: &str, language: &LanguageDefinition) -> Result<bool> {
+    let Some(grammar) = language.grammar() else {
+        return Ok(true);
+    };
+
+    let mut parser = Parser::new();
+    parser
+        .set_language(&grammar)
+        .context("Could not set language on parser")?;
+
+    let tree = parser
+        .parse(source.as_bytes(), None)
+        .context("Could not parse file")?;
+
+    Ok(!tree.root_node().has_error())
+}
+
+/// A unified diff from `old` to `new`, the contents of the file at `path`.
+/// Edits never add or remove lines, so each line of `old` is compared to the
+/// same line of `new`.
+pub fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let old_lines = old.split_inclusive('\n').collect::<Vec<_>>();
+    let new_lines = new.split_inclusive('\n').collect::<Vec<_>>();
+
+    let changed = (0..old_lines.len().min(new_lines.len()))
+        .filter(|&line| old_lines[line] != new_lines[line])
+        .collect::<Vec<_>>();
+
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    let path = path.display();
+    let mut diff = format!("--- a/{path}\n+++ b/{path}\n");
+
+    // Changes close enough for their context to touch share a hunk.
+    let mut hunks = Vec::<Vec<usize>>::new();
+    for line in changed {
+        match hunks.last_mut() {
+            Some(hunk) if line - hunk[hunk.len() - 1] <= 2 * DIFF_CONTEXT => hunk.push(line),
+            _ => hunks.push(vec![line]),
+        }
+    }
+
+    let push_line = |diff: &mut String, prefix: char, line: &str| {
+        diff.push(prefix);
+        diff.push_str(line);
+        if !line.ends_with('\n') {
+            diff.push_str("\n\\ No newline at end of file\n");
+        }
+    };
+
+    for hunk in hunks {
+        let start = hunk[0].saturating_sub(DIFF_CONTEXT);
+        let end = (hunk[hunk.len() - 1] + DIFF_CONTEXT + 1).min(old_lines.len());
+
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            start + 1,
+            end - start,
+            start + 1,
+            end - start
+        ));
+
+        let mut line = start;
+        while line < end {
+            if !hunk.contains(&line) {
+                push_line(&mut diff, ' ', old_lines[line]);
+                line += 1;
+                continue;
+            }
+
+            // A run of changed lines is shown as removed, then as added.
+            let run_end = (line..end).find(|line| !hunk.contains(line)).unwrap_or(end);
+            for old in &old_lines[line..run_end] {
+                push_line(&mut diff, '-', old);
+            }
+            for new in &new_lines[line..run_end] {
+                push_line(&mut diff, '+', new);
+            }
+            line = run_end;
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{
+        dictionary::word_list::WordList,
+        parsing::{
+            parser::{Forbidden, Severity},
+            word_separator::{Span, Word},
+        },
+    };
+
+    /// The edit for the word at `start..end` of `source`.
+    fn edit_at(
+        source: &str,
+        start: usize,
+        end: usize,
+        problem: Problem,
+        words: &[&str],
+    ) -> Option<Edit> {
+        let unknown = UnknownWord {
+            word: Word {
+                text: source[start..end].to_lowercase(),
+                span: Span {
+                    start,
+                    end,
+                    line: 0,
+                    column: start,
+                },
+            },
+            node_kind: "identifier",
+            language: "typescript",
+            problem,
+            severity: Severity::Error,
+            suggestions: Vec::new(),
+        };
+        let dictionary = WordList::new("test", words.iter().map(|word| word.to_string()));
+
+        edit(source, &unknown, &dictionary, 0.75)
+    }
+
+    #[test]
+    fn keeps_the_casing_style_of_identifiers() {
+        assert_eq!(match_casing("recieve", "receive"), "receive");
+        assert_eq!(match_casing("Recieve", "receive"), "Receive");
+        assert_eq!(match_casing("RECIEVE", "receive"), "RECEIVE");
+        assert_eq!(match_casing("githib", "GitHub"), "GitHub");
+        assert_eq!(match_casing("GITHIB", "GitHub"), "GITHUB");
+
+        let source = "getRecieveCount(); RECIEVE_LIMIT; recieve_all";
+        let edits = [(3, 10), (19, 26), (34, 41)]
+            .into_iter()
+            .map(|(start, end)| {
+                edit_at(source, start, end, Problem::Unknown, &["receive"]).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            apply(source, &edits),
+            "getReceiveCount(); RECEIVE_LIMIT; receive_all"
+        );
+    }
+
+    #[test]
+    fn only_fixes_clear_corrections() {
+        // "hello" and "help" are as close to "helo".
+        assert_eq!(
+            edit_at("helo", 0, 4, Problem::Unknown, &["hello", "help"]),
+            None
+        );
+        // "wrlod" is two edits from "world", too far for its length.
+        assert_eq!(edit_at("wrlod", 0, 5, Problem::Unknown, &["world"]), None);
+        // A single transposition in a short word is confident enough.
+        assert_eq!(
+            edit_at("teh", 0, 3, Problem::Unknown, &["the"]),
+            Some(Edit {
+                range: 0..3,
+                replacement: "the".to_string()
+            })
+        );
+        assert_eq!(
+            edit_at("Helo", 0, 4, Problem::Unknown, &["hello", "world"]),
+            Some(Edit {
+                range: 0..4,
+                replacement: "Hello".to_string()
+            })
+        );
+
+        let forbidden = Problem::Forbidden(Forbidden {
+            layer: "flag-words".to_string(),
+            replacement: Some("denylist".to_string()),
+            message: None,
+        });
+        assert_eq!(
+            edit_at("Blacklist", 0, 9, forbidden, &[])
+                .unwrap()
+                .replacement,
+            "Denylist"
+        );
+
+        let casing = Problem::Casing {
+            expected: vec!["GitHub".to_string()],
+        };
+        assert_eq!(
+            edit_at("github", 0, 6, casing, &[]).unwrap().replacement,
+            "GitHub"
+        );
+    }
+
+    #[test]
+    fn drops_overlapping_edits() {
+        let edit = |range: Range<usize>, replacement: &str| Edit {
+            range,
+            replacement: replacement.to_string(),
+        };
+
+        assert_eq!(
+            apply(
+                "teh naïve wrold",
+                &[
+                    edit(11..16, "world"),
+                    edit(0..3, "the"),
+                    edit(11..16, "world"),
+                    edit(13..15, "ro"),
+                ]
+            ),
+            "the naïve world"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "lang-typescript")]
+    fn checks_that_fixed_code_still_parses() {
+        let typescript = crate::parsing::language::for_extension("ts").unwrap();
+
+        assert!(parses("const recieve = 1;", typescript).unwrap());
+        assert!(!parses("const re-ceive = 1;", typescript).unwrap());
+    }
+
+    #[test]
+    fn writes_changed_lines_as_unified_diff() {
+        let old = "a\nb\nc\nd\nwrold\nf\ng\nh\ni\nj\nk\nl\nwrold";
+        let new = "a\nb\nc\nd\nworld\nf\ng\nh\ni\nj\nk\nl\nworld";
+
+        assert_eq!(
+            unified_diff(&PathBuf::from("notes.txt"), old, new),
+            "--- a/notes.txt
++++ b/notes.txt
+@@ -2,7 +2,7 @@
+ b
+ c
+ d
+-wrold
++world
+ f
+ g
+ h
+@@ -10,4 +10,4 @@
+ j
+ k
+ l
+-wrold
+\\ No newline at end of file
++world
+\\ No newline at end of file
+"
+        );
+        assert_eq!(unified_diff(&PathBuf::from("notes.txt"), old, old), "");
+    }
+}
@@ -1,55 +1,629 @@
 use anyhow::{Context, Result};
-use clap::Parser;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use std::{collections::HashSet, fs, time::Instant};
-use tree_sitter_typescript::LANGUAGE_TYPESCRIPT;
+use clap::{Parser, Subcommand};
+use rayon::iter::{IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator};
+use std::{fs, path::PathBuf, process::ExitCode, sync::Arc, time::Instant};
 
+mod config;
+mod dictionary;
+mod fix;
 mod parsing;
+mod reporting;
+mod suggest;
+
+use config::Config;
+use dictionary::{
+    Dictionary,
+    compiled::{self, CompileOptions},
+    flag_words::FlagWords,
+    layered::{Layer, LayeredDictionary, Lookup},
+    pattern::PatternDictionary,
+    word_list::WordList,
+};
+use parsing::{
+    ignore_patterns::IgnorePatterns,
+    language::{self, LanguageDefinition, NodeCategory},
+    parser::{
+        CaseSensitivity, CheckOptions, Checker, Checkers, FileReport, Severity, UnknownWord,
+        check_source,
+    },
+    query::{QuerySources, SpellQuery},
+};
+use reporting::{OutputFormat, Summary};
 
 /// A tool to check for typos in code.
+///
+/// Settings are read from the first rspell.toml or cspell.json found in the
+/// current directory or its parents, and the options below override them.
+///
+/// Exits with 0 if no errors are found, 1 if there are errors or more
+/// warnings than --max-warnings, and 2 if rspell could not run.
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// A glob path to the files to check, e.g. 'src/**/*.ts'
-    path: String,
+    #[arg(required = true)]
+    path: Option<String>,
+
+    /// The configuration file to use instead of looking for one
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Globs of the dictionaries to load
+    #[arg(long = "dictionary")]
+    dictionaries: Vec<String>,
+
+    /// Kinds of nodes not to check
+    #[arg(long, value_enum, value_delimiter = ',')]
+    skip: Vec<NodeCategory>,
+
+    /// Kinds of nodes in which words like 'readfile' are accepted if they
+    /// split into dictionary words
+    #[arg(long, value_enum, value_delimiter = ',')]
+    compound_words: Vec<NodeCategory>,
+
+    /// Check code blocks like Markdown code fences with the grammar of the
+    /// language they name
+    #[arg(long)]
+    check_code_blocks: bool,
+
+    /// A directory of tree-sitter queries named after their language, e.g.
+    /// 'typescript.scm', adding to the built-in ones
+    #[arg(long)]
+    queries: Option<PathBuf>,
+
+    /// Use the queries in --queries instead of the built-in ones
+    #[arg(long)]
+    replace_queries: bool,
+
+    /// Words shorter than this are not checked
+    #[arg(long)]
+    min_word_length: Option<usize>,
+
+    /// The number of suggestions to show for each unknown word
+    #[arg(long)]
+    suggestions: Option<usize>,
+
+    /// Where words must match the casing of dictionary entries like
+    /// 'GitHub'
+    #[arg(long, value_enum)]
+    case_sensitivity: Option<CaseSensitivity>,
+
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+
+    /// Fail once there are more warnings than this
+    #[arg(long)]
+    max_warnings: Option<usize>,
+
+    /// Correct words in place with their best suggestion, when it is clearly
+    /// the right one
+    #[arg(long)]
+    fix: bool,
+
+    /// Print the corrections --fix would make as a unified diff instead of
+    /// making them
+    #[arg(long, conflicts_with = "fix")]
+    fix_dry_run: bool,
+
+    /// How close the best suggestion must be for --fix to apply it, from 0
+    /// to 1
+    #[arg(long, value_parser = config::parse_fix_confidence)]
+    fix_confidence: Option<f64>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compile dictionaries into a single file that loads without parsing
+    CompileDict(CompileDictArgs),
+    /// Show which dictionary layer accepts or forbids each word
+    Lookup(LookupArgs),
 }
 
-type Dictionary = HashSet<String>;
+#[derive(clap::Args, Debug)]
+struct CompileDictArgs {
+    /// Globs of the dictionaries to compile
+    #[arg(required = true)]
+    inputs: Vec<String>,
+
+    /// The file to write, which should have the .rsdict extension to be
+    /// recognised as a compiled dictionary
+    #[arg(short, long)]
+    output: PathBuf,
 
-fn load_dictionaries(glob_path: &str) -> Result<Dictionary> {
-    let files = glob::glob(glob_path).unwrap();
+    /// Keep the case of words instead of lowercasing them
+    #[arg(long)]
+    keep_case: bool,
 
-    let mut dictionary = HashSet::with_capacity(500_000);
+    /// Fail on repeated words instead of dropping them
+    #[arg(long)]
+    no_dedup: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct LookupArgs {
+    #[arg(required = true)]
+    words: Vec<String>,
+
+    /// Include the dictionaries of this language, e.g. 'rust'
+    #[arg(long)]
+    language: Option<String>,
+}
 
-    for file in files {
-        let file = fs::read_to_string(file.context("Failed to read file")?)?;
+impl Args {
+    /// Applies the options given on the command line on top of `config`.
+    fn override_config(self, config: &mut Config) {
+        if !self.dictionaries.is_empty() {
+            config.dictionaries = self.dictionaries;
+        }
+        if !self.skip.is_empty() {
+            config.skip = self.skip;
+        }
+        if !self.compound_words.is_empty() {
+            config.compound_words = self.compound_words;
+        }
+        config.check_code_blocks |= self.check_code_blocks;
+        if let Some(queries) = self.queries {
+            config.queries = Some(queries);
+        }
+        config.replace_queries |= self.replace_queries;
+        if let Some(min_word_length) = self.min_word_length {
+            config.min_word_length = min_word_length;
+        }
+        if let Some(suggestions) = self.suggestions {
+            config.suggestions = suggestions;
+        }
+        if let Some(case_sensitivity) = self.case_sensitivity {
+            config.case_sensitivity = case_sensitivity;
+        }
+        if let Some(format) = self.format {
+            config.format = format;
+        }
+        if let Some(max_warnings) = self.max_warnings {
+            config.max_warnings = Some(max_warnings);
+        }
+        if let Some(fix_confidence) = self.fix_confidence {
+            config.fix_confidence = fix_confidence;
+        }
+    }
+}
 
-        dictionary.extend(file.lines().map(|line| line.to_string()));
+fn compile_dict(args: CompileDictArgs) -> Result<()> {
+    let mut words = Vec::new();
+    for glob in &args.inputs {
+        words.extend(dictionary::read_words(glob)?);
+    }
+
+    let options = CompileOptions {
+        fold_case: !args.keep_case,
+        dedup: !args.no_dedup,
+    };
+
+    let count = compiled::compile(words, &args.output, options)?;
+
+    println!("[*] Compiled {count} words into {}", args.output.display());
+
+    Ok(())
+}
+
+/// Loads every dictionary matching one of `globs`, warning about globs that
+/// match nothing.
+fn load_dictionaries(globs: &[String]) -> Result<Vec<Arc<dyn Dictionary>>> {
+    let mut dictionaries = Vec::new();
+    for glob in globs {
+        let loaded = dictionary::load(glob)?;
+
+        if loaded.is_empty() {
+            eprintln!("warning: no dictionaries match {glob}");
+        }
+
+        dictionaries.extend(loaded);
+    }
+
+    Ok(dictionaries)
+}
+
+/// The words and patterns given directly in the configuration, as
+/// dictionaries named after `source`.
+fn inline_dictionaries(
+    source: &str,
+    words: &[String],
+    word_patterns: &[String],
+) -> Result<Vec<Arc<dyn Dictionary>>> {
+    let mut dictionaries = Vec::<Arc<dyn Dictionary>>::new();
+
+    if !words.is_empty() {
+        dictionaries.push(Arc::new(WordList::new(source, words.iter().cloned())));
+    }
+    if !word_patterns.is_empty() {
+        dictionaries.push(Arc::new(PatternDictionary::new(source, word_patterns)?));
+    }
+
+    Ok(dictionaries)
+}
+
+/// The layers stacked on top of the base dictionaries of every language:
+/// those configured in `layers`, then the project's words, then the user's
+/// dictionaries, and finally the flagged words, which nothing can allow.
+fn dictionary_layers(config: &Config) -> Result<Vec<Layer>> {
+    let mut layers = Vec::new();
+
+    for layer_config in &config.layers {
+        let mut dictionaries = load_dictionaries(&layer_config.dictionaries)?;
+        dictionaries.extend(inline_dictionaries(
+            &layer_config.name,
+            &layer_config.words,
+            &layer_config.word_patterns,
+        )?);
+
+        layers.push(if layer_config.forbidden {
+            Layer::forbid(&layer_config.name, dictionaries)
+        } else {
+            Layer::allow(&layer_config.name, dictionaries)
+        });
+    }
+
+    let mut project = inline_dictionaries("config", &config.words, &config.word_patterns)?;
+    // Ignored words are accepted, but should never be suggested.
+    project.push(Arc::new(
+        WordList::new("ignore-words", config.ignore_words.iter().cloned()).without_suggestions(),
+    ));
+    layers.push(Layer::allow("project", project));
+
+    layers.push(Layer::allow(
+        "user",
+        load_dictionaries(&config.user_dictionary_globs())?,
+    ));
+
+    layers.push(Layer::forbid(
+        "flag-words",
+        vec![Arc::new(FlagWords::new(
+            "flag-words",
+            config.flag_words.iter().cloned(),
+        ))],
+    ));
+
+    Ok(layers)
+}
+
+/// Stacks `layers` on top of `base` and the built-in and configured
+/// dictionaries of `language`.
+fn language_dictionary(
+    config: &Config,
+    base: &[Arc<dyn Dictionary>],
+    layers: &[Layer],
+    language: Option<&LanguageDefinition>,
+) -> Result<LayeredDictionary> {
+    let mut base = base.to_vec();
+
+    if let Some(language) = language {
+        if !language.words.is_empty() {
+            base.push(Arc::new(WordList::new(
+                format!("built-in {} words", language.name),
+                language.words.lines().map(String::from),
+            )));
+        }
+        base.extend(load_dictionaries(
+            &config.language_dictionary_globs(language.config_name()),
+        )?);
+    }
+
+    let mut dictionary = LayeredDictionary::default();
+    dictionary.push(Layer::allow("base", base));
+    for layer in layers {
+        dictionary.push(layer.clone());
     }
 
     Ok(dictionary)
 }
 
-fn main() -> Result<()> {
-    let args = Args::parse();
+fn lookup(config: &Config, args: LookupArgs) -> Result<()> {
+    let base = load_dictionaries(&config.dictionaries)?;
+    let layers = dictionary_layers(config)?;
+    let language = args
+        .language
+        .map(|name| language::by_name(&name).with_context(|| format!("Unknown language {name:?}")))
+        .transpose()?;
+    let dictionary = language_dictionary(config, &base, &layers, language)?;
+
+    for word in args.words {
+        match dictionary.lookup(&word.to_lowercase()) {
+            Lookup::Accepted { layer, source, .. } => {
+                println!("{word}: accepted by {layer} ({source})");
+            }
+            Lookup::Forbidden {
+                layer,
+                source,
+                flag,
+            } => match flag.and_then(|flag| flag.replacement.as_ref()) {
+                Some(replacement) => {
+                    println!("{word}: forbidden by {layer} ({source}), use {replacement}");
+                }
+                None => println!("{word}: forbidden by {layer} ({source})"),
+            },
+            Lookup::Unknown => println!("{word}: unknown"),
+        }
+    }
+
+    Ok(())
+}
 
-    let files = glob::glob(&args.path)
+/// Fills in the suggestions for the words of `report`, up to `limit` each.
+fn suggest(report: &mut FileReport, checkers: &Checkers, limit: usize) {
+    // Flagged words come with their replacement already.
+    for unknown in report
+        .unknown_words
+        .iter_mut()
+        .filter(|unknown| unknown.suggestions.is_empty())
+    {
+        unknown.suggestions = dictionary_of(checkers, unknown).suggest(&unknown.word.text, limit);
+    }
+}
+
+/// The dictionary `unknown` was checked against, that of the language it is
+/// written in, which for embedded code is not the language of the file.
+fn dictionary_of<'a>(checkers: &'a Checkers, unknown: &UnknownWord) -> &'a LayeredDictionary {
+    &checkers[unknown.language].dictionary
+}
+
+/// Corrects the words in `reports` that have a clearly right correction, see
+/// `fix::edit`, and checks the fixed files again so their reports hold the
+/// issues left. A dry run prints a diff of the corrections instead. Files
+/// that parsed before but not with the corrections are left alone.
+fn fix_files(
+    reports: &mut [FileReport],
+    files: &[(PathBuf, &LanguageDefinition)],
+    checkers: &Checkers,
+    config: &Config,
+    dry_run: bool,
+) -> Result<()> {
+    let mut fixed_words = 0;
+    let mut fixed_files = 0;
+
+    for (report, (path, language)) in reports.iter_mut().zip(files) {
+        let edits = report
+            .unknown_words
+            .iter()
+            .filter_map(|unknown| {
+                let dictionary = dictionary_of(checkers, unknown);
+                fix::edit(&report.source, unknown, dictionary, config.fix_confidence)
+            })
+            .collect::<Vec<_>>();
+
+        if edits.is_empty() {
+            continue;
+        }
+
+        let fixed = fix::apply(&report.source, &edits);
+
+        if fix::parses(&report.source, language)? && !fix::parses(&fixed, language)? {
+            eprintln!(
+                "warning: not fixing {}, as it does not parse with the corrections",
+                path.display()
+            );
+            continue;
+        }
+
+        fixed_words += edits.len();
+        fixed_files += 1;
+
+        if dry_run {
+            print!("{}", fix::unified_diff(path, &report.source, &fixed));
+            continue;
+        }
+
+        fs::write(path, &fixed).with_context(|| format!("Could not write {}", path.display()))?;
+
+        report.unknown_words = check_source(&fixed, language, checkers)
+            .with_context(|| format!("Could not check {} as {}", path.display(), language.name))?;
+        report.source = fixed;
+        suggest(report, checkers, config.suggestions);
+    }
+
+    let verb = if dry_run { "Would fix" } else { "Fixed" };
+    eprintln!("[*] {verb} {fixed_words} words in {fixed_files} files");
+
+    Ok(())
+}
+
+/// Whether the issues in `reports` fail the run: any error does, and
+/// warnings do once there are more than `max_warnings` of them.
+fn failed(reports: &[FileReport], max_warnings: Option<usize>) -> bool {
+    let warnings = reporting::count(reports, Severity::Warning);
+
+    if let Some(max_warnings) = max_warnings
+        && warnings > max_warnings
+    {
+        eprintln!("error: too many warnings ({warnings}), the maximum is {max_warnings}");
+        return true;
+    }
+
+    reporting::count(reports, Severity::Error) > 0
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(true) => ExitCode::FAILURE,
+        Ok(false) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {error:?}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Runs the command given on the command line, returning whether it found
+/// issues that fail it.
+fn run() -> Result<bool> {
+    let mut args = Args::parse();
+
+    let command = args.command.take();
+    if let Some(Command::CompileDict(compile_args)) = command {
+        compile_dict(compile_args)?;
+        return Ok(false);
+    }
+
+    let mut config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::discover()?,
+    };
+
+    for warning in &config.warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    if let Some(Command::Lookup(lookup_args)) = command {
+        args.override_config(&mut config);
+        lookup(&config, lookup_args)?;
+        return Ok(false);
+    }
+
+    let path = args.path.take().context("No path given")?;
+    let (fix, fix_dry_run) = (args.fix, args.fix_dry_run);
+    args.override_config(&mut config);
+
+    // Files without a known or enabled language are skipped.
+    let mut files = Vec::new();
+    for file in glob::glob(&path)
         .context("Failed to glob")?
         .filter_map(Result::ok)
-        .collect::<Vec<_>>();
+    {
+        let Some(language) = language::for_path(&file) else {
+            continue;
+        };
+
+        if config.is_enabled(language.name) && !config.is_ignored(&file)? {
+            files.push((file, language));
+        }
+    }
+
+    let base = load_dictionaries(&config.dictionaries)?;
+    let layers = dictionary_layers(&config)?;
 
-    let dictionary = load_dictionaries("dictionaries/*")?;
-    println!("{:?}", dictionary);
+    if let Some(dir) = &config.queries
+        && !language::LANGUAGES
+            .iter()
+            .any(|language| dir.join(format!("{}.scm", language.name)).is_file())
+    {
+        eprintln!("warning: no queries found in {}", dir.display());
+    }
+
+    let sources = QuerySources {
+        dir: config.queries.as_deref(),
+        replace_builtin: config.replace_queries,
+    };
+
+    // Every enabled language has a checker, as any of them can be embedded
+    // in the files being checked, like code in Markdown.
+    let mut checkers = Checkers::new();
+    for language in language::LANGUAGES
+        .iter()
+        .filter(|language| config.is_enabled(language.config_name()))
+    {
+        let name = language.config_name();
+        let checker = Checker {
+            query: SpellQuery::new(language, &sources)?,
+            options: CheckOptions {
+                categories: config.categories(name),
+                min_word_length: config.min_word_length,
+                case_sensitivity: config.case_sensitivity,
+                compound_categories: config.compound_categories(name),
+                compound: config.compound_options(),
+                check_code_blocks: config.check_code_blocks,
+                ignore_patterns: IgnorePatterns::new(&config.ignore_patterns(name))?,
+                severities: config.severity.clone(),
+            },
+            dictionary: language_dictionary(&config, &base, &layers, Some(language))?,
+        };
+
+        checkers.insert(language.name, checker);
+    }
 
-    let now = Instant::now();
+    let started = Instant::now();
 
-    let result = files
+    // Files that cannot be read or are not UTF-8 are skipped with a warning,
+    // rather than failing the check of every other file.
+    let reports = files
         .par_iter()
-        .map(|file| parsing::parser::parse_file(file, &LANGUAGE_TYPESCRIPT.into()));
+        .map(|(file, language)| {
+            let source = match parsing::parser::read_source(file) {
+                Ok(source) => source,
+                Err(error) => {
+                    eprintln!("warning: {error:#}, skipping it");
+                    return Ok(None);
+                }
+            };
 
-    result.for_each(|_| {});
+            parsing::parser::check_file(file, source, language, &checkers).map(Some)
+        })
+        .collect::<Result<Vec<_>>>()?;
 
-    println!("[*] Done with {} files in {:?}", files.len(), now.elapsed());
+    let (files, mut reports): (Vec<_>, Vec<_>) = files
+        .into_iter()
+        .zip(reports)
+        .filter_map(|(file, report)| Some((file, report?)))
+        .unzip();
 
-    Ok(())
+    let check_time = started.elapsed();
+    let started = Instant::now();
+
+    // Dictionaries only build their suggestion index when first asked, so
+    // clean runs never pay for it.
+    if config.suggestions > 0 {
+        reports
+            .par_iter_mut()
+            .for_each(|report| suggest(report, &checkers, config.suggestions));
+    }
+
+    let summary = Summary {
+        files: files.len(),
+        check_time,
+        suggest_time: started.elapsed(),
+    };
+
+    if fix || fix_dry_run {
+        fix_files(&mut reports, &files, &checkers, &config, fix_dry_run)?;
+    }
+
+    // A dry run prints the diff in place of the issues.
+    if !fix_dry_run {
+        reporting::emit(&reports, &summary, config.format, &config.severity)?;
+    }
+
+    Ok(failed(&reports, config.max_warnings))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::reporting::test::report_at;
+
+    /// Whether a run fails with an issue of each of `severities`.
+    fn fails_with(severities: [Severity; 2], max_warnings: Option<usize>) -> bool {
+        failed(&[report_at("src/typo.ts", severities)], max_warnings)
+    }
+
+    #[test]
+    fn fails_on_errors_and_too_many_warnings() {
+        use Severity::{Error, Info, Warning};
+
+        assert!(fails_with([Warning, Error], None));
+        assert!(fails_with([Warning, Error], Some(5)));
+
+        // Warnings fail only above the maximum.
+        assert!(!fails_with([Warning, Warning], None));
+        assert!(!fails_with([Warning, Warning], Some(2)));
+        assert!(fails_with([Warning, Warning], Some(1)));
+
+        assert!(!fails_with([Info, Info], Some(0)));
+        assert!(!failed(&[], Some(0)));
+    }
 }
@@ -0,0 +1,179 @@
+use std::{collections::HashMap, ops::Range};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{
+    ranges::{merge_ranges, overlaps_any},
+    word_separator::Word,
+};
+
+/// The effect of the `rspell:` directives in the comments of a file. The
+/// equivalent `cspell:` directives are honoured as well.
+///
+/// - `rspell:disable` stops checking until the next `rspell:enable`.
+/// - `rspell:disable-next-line` skips the line after the directive.
+/// - `rspell:ignore foo bar` and `rspell:words foo bar` accept the listed
+///   words from the directive onwards.
+#[derive(Debug, Default)]
+pub struct Directives {
+    /// Sorted and merged, see `merge_ranges`.
+    disabled: Vec<Range<usize>>,
+    /// Accepted words, with the byte offset of the first directive listing
+    /// them.
+    words: HashMap<String, usize>,
+}
+
+static DIRECTIVE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:rspell|cspell):(disable-next-line|disable|enable|ignore|words)\b([^\n]*)")
+        .unwrap()
+});
+
+impl Directives {
+    /// Collects the directives in `comments`, given as the byte offset and
+    /// text of each comment in `source`, in document order. The pattern is
+    /// matched by a regular expression engine without backtracking, so that
+    /// comments of any length take linear time.
+    pub fn parse<'a>(source: &str, comments: impl IntoIterator<Item = (usize, &'a str)>) -> Self {
+        let mut directives = Directives::default();
+        let mut disabled_since = None;
+
+        for (offset, comment) in comments {
+            for captures in DIRECTIVE.captures_iter(comment) {
+                let directive = captures.get(0).unwrap();
+                let start = offset + directive.start();
+                let end = offset + directive.end();
+                let arguments = captures.get(2).unwrap().as_str();
+
+                // The directive itself should not be checked.
+                directives.disabled.push(start..end);
+
+                match &captures[1] {
+                    "disable" => {
+                        disabled_since.get_or_insert(start);
+                    }
+                    "enable" => {
+                        if let Some(since) = disabled_since.take() {
+                            directives.disabled.push(since..start);
+                        }
+                    }
+                    "disable-next-line" => {
+                        if let Some(next_line) = line_after(source, end) {
+                            directives.disabled.push(next_line);
+                        }
+                    }
+                    _ => {
+                        for word in arguments
+                            .split_whitespace()
+                            .map(|word| word.trim_end_matches("*/"))
+                            .filter(|word| word.chars().any(char::is_alphabetic))
+                        {
+                            let since =
+                                directives.words.entry(word.to_lowercase()).or_insert(start);
+                            *since = (*since).min(start);
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(since) = disabled_since {
+            directives.disabled.push(since..source.len());
+        }
+
+        directives.disabled = merge_ranges(directives.disabled);
+
+        directives
+    }
+
+    /// Whether `word` is exempt from checking by a directive.
+    pub fn allows(&self, word: &Word) -> bool {
+        let start = word.span.start;
+
+        overlaps_any(&self.disabled, start..start + 1)
+            || self
+                .words
+                .get(&word.text)
+                .is_some_and(|since| *since <= start)
+    }
+}
+
+/// The byte range of the line following the one containing `offset`.
+fn line_after(source: &str, offset: usize) -> Option<Range<usize>> {
+    let start = offset + source[offset..].find('\n')? + 1;
+    let end = source[start..]
+        .find('\n')
+        .map_or(source.len(), |length| start + length);
+
+    Some(start..end)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parsing::word_separator::{Position, extract_words};
+
+    /// The words of `source` not exempted by the directives in its
+    /// comments, which run from `//` or `/*` to the end of the line.
+    fn checked_words(source: &str) -> Vec<String> {
+        let mut offset = 0;
+        let comments = source
+            .split_inclusive('\n')
+            .map(|line| {
+                let start = offset;
+                offset += line.len();
+                line.find("//")
+                    .or_else(|| line.find("/*"))
+                    .map(|index| (start + index, &line[index..]))
+            })
+            .collect::<Vec<_>>();
+
+        let directives = Directives::parse(source, comments.into_iter().flatten());
+
+        extract_words(source, Position::default(), 3)
+            .filter(|word| !directives.allows(word))
+            .map(|word| word.text)
+            .collect()
+    }
+
+    #[test]
+    fn disables_until_enabled() {
+        assert_eq!(
+            checked_words("one\n// rspell:disable\ntwo\n// rspell:enable\nthree\n"),
+            ["one", "three"]
+        );
+    }
+
+    #[test]
+    fn disables_until_end_of_file() {
+        assert_eq!(checked_words("one\n// cspell:disable\ntwo\n"), ["one"]);
+    }
+
+    #[test]
+    fn disables_next_line() {
+        assert_eq!(
+            checked_words("one\n// rspell:disable-next-line\ntwo\nthree"),
+            ["one", "three"]
+        );
+    }
+
+    #[test]
+    fn ignores_words_after_directive() {
+        assert_eq!(
+            checked_words(
+                "wrlod\n/* rspell:ignore Wrlod helo */\nwrlod helo hello\n// cspell:words hello\nhello"
+            ),
+            ["wrlod", "hello"]
+        );
+    }
+
+    #[test]
+    fn finds_directives_in_large_comments() {
+        let source = format!(
+            "// {}\n// rspell:ignore tset\ntset\n",
+            "ordinary words ".repeat(70_000)
+        );
+
+        assert!(!checked_words(&source).contains(&"tset".to_string()));
+    }
+}
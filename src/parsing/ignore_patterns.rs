@@ -0,0 +1,232 @@
+//! Regular expressions masking text that is not made of words, like hashes
+//! or URLs, which would otherwise be split into nonsense fragments.
+
+use std::ops::Range;
+
+use anyhow::{Context, Result};
+
+/// Built-in patterns, configured by name.
+const PRESETS: [(&str, &str); 6] = [
+    (
+        "urls",
+        r#"\b(?:https?|ftp|file)://[^\s<>()\[\]"'`]+|\bwww\.[^\s<>()\[\]"'`]+"#,
+    ),
+    // Hex literals, colors, and hashes with at least one digit, so that words
+    // like "defaced" are still checked.
+    (
+        "hex",
+        r"(?i)\b0x[0-9a-f]+\b|#[0-9a-f]{3,8}\b|\b(?=[0-9a-f]*\d)[0-9a-f]{7,}\b",
+    ),
+    (
+        "uuid",
+        r"(?i)\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b",
+    ),
+    // Long runs of mixed case letters and digits, which identifiers rarely
+    // are.
+    (
+        "base64",
+        r"(?<![A-Za-z0-9+/])(?=[A-Za-z0-9+/]*\d)(?=[A-Za-z0-9+/]*[A-Z])(?=[A-Za-z0-9+/]*[a-z])[A-Za-z0-9+/]{40,}={0,2}",
+    ),
+    ("email", r"\b[\w.+-]+@[\w-]+(?:\.[\w-]+)+\b"),
+    (
+        "semver",
+        r"\bv?\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?\b",
+    ),
+];
+
+/// The names cspell uses for some of the presets.
+const ALIASES: [(&str, &str); 3] = [("hexvalues", "hex"), ("hexdigits", "hex"), ("href", "urls")];
+
+/// A set of patterns whose matches are not checked. Each pattern is the
+/// name of a preset (`Urls`, `Hex`, `Uuid`, `Base64`, `Email`, `SemVer`), a
+/// regular expression, or a regular expression written as `/pattern/flags`
+/// like in cspell.
+#[derive(Debug, Clone, Default)]
+pub struct IgnorePatterns {
+    patterns: Vec<Pattern>,
+}
+
+/// A compiled pattern. Patterns without look-around or backreferences run
+/// in linear time on the `regex` engine. The others need the backtracking
+/// engine of `fancy_regex`, whose backtracking limit is counted per search,
+/// so they are matched one line at a time.
+#[derive(Debug, Clone)]
+enum Pattern {
+    Linear(regex::Regex),
+    Backtracking(fancy_regex::Regex),
+}
+
+impl IgnorePatterns {
+    pub fn new(entries: &[String]) -> Result<Self> {
+        let patterns = entries
+            .iter()
+            .map(|entry| {
+                let pattern = expand(entry);
+
+                match regex::Regex::new(&pattern) {
+                    Ok(regex) => Ok(Pattern::Linear(regex)),
+                    Err(_) => fancy_regex::Regex::new(&pattern)
+                        .map(Pattern::Backtracking)
+                        .with_context(|| format!("Invalid ignore pattern {entry:?}")),
+                }
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(IgnorePatterns { patterns })
+    }
+
+    /// The byte ranges of `text` matched by any of the patterns. Fails if a
+    /// pattern cannot be matched on a line, as it backtracks too much.
+    pub fn find_in(&self, text: &str) -> Result<Vec<Range<usize>>> {
+        let mut ranges = Vec::new();
+
+        for pattern in &self.patterns {
+            match pattern {
+                Pattern::Linear(regex) => ranges.extend(
+                    regex
+                        .find_iter(text)
+                        .map(|found| found.range())
+                        .filter(|range| !range.is_empty()),
+                ),
+                Pattern::Backtracking(regex) => {
+                    let mut start = 0;
+
+                    for line in text.split_inclusive('\n') {
+                        for found in regex.find_iter(line) {
+                            let found = found.with_context(|| {
+                                format!("Could not match ignore pattern {:?}", regex.as_str())
+                            })?;
+
+                            if !found.range().is_empty() {
+                                ranges.push(start + found.start()..start + found.end());
+                            }
+                        }
+
+                        start += line.len();
+                    }
+                }
+            }
+        }
+
+        Ok(ranges)
+    }
+}
+
+/// The regular expression an entry stands for.
+fn expand(entry: &str) -> String {
+    let name = entry.to_lowercase();
+    let name = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name.as_str(), |(_, preset)| preset);
+
+    if let Some((_, pattern)) = PRESETS.iter().find(|(preset, _)| *preset == name) {
+        return pattern.to_string();
+    }
+
+    // `/pattern/flags`, of which only the flags changing what matches are
+    // kept.
+    if let Some((pattern, flags)) = entry
+        .strip_prefix('/')
+        .and_then(|rest| rest.rsplit_once('/'))
+    {
+        let flags = flags
+            .chars()
+            .filter(|flag| matches!(flag, 'i' | 'm' | 's' | 'x'))
+            .collect::<String>();
+
+        if flags.is_empty() {
+            return pattern.to_string();
+        }
+
+        return format!("(?{flags}){pattern}");
+    }
+
+    entry.to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn masked<'a>(entries: &[&str], text: &'a str) -> Vec<&'a str> {
+        let patterns = IgnorePatterns::new(
+            &entries
+                .iter()
+                .map(|entry| entry.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        patterns
+            .find_in(text)
+            .unwrap()
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn matches_presets() {
+        assert_eq!(
+            masked(
+                &["Urls"],
+                "see https://example.com/docs) or www.example.org"
+            ),
+            ["https://example.com/docs", "www.example.org"]
+        );
+        assert_eq!(
+            masked(&["Hex"], "color: #ff00aa; commit 3f2a9c1e, defaced 0xDEAD"),
+            ["#ff00aa", "3f2a9c1e", "0xDEAD"]
+        );
+        assert_eq!(
+            masked(&["Uuid"], "id 123e4567-e89b-12d3-a456-426614174000"),
+            ["123e4567-e89b-12d3-a456-426614174000"]
+        );
+        assert_eq!(
+            masked(&["Email"], "mail jane.doe+spam@example.co.uk today"),
+            ["jane.doe+spam@example.co.uk"]
+        );
+        assert_eq!(
+            masked(&["SemVer"], "since v1.2.3-beta.1 and 2.0.0"),
+            ["v1.2.3-beta.1", "2.0.0"]
+        );
+        assert_eq!(
+            masked(
+                &["Base64"],
+                "key: dGhpcyBpcyBhIHNlY3JldCBrZXkgZm9yIHRlc3Rpbmc= and getElementById"
+            ),
+            ["dGhpcyBpcyBhIHNlY3JldCBrZXkgZm9yIHRlc3Rpbmc="]
+        );
+    }
+
+    #[test]
+    fn reads_cspell_patterns() {
+        assert_eq!(
+            masked(&["/TODO\\(\\w+\\)/gi"], "todo(jane): fix"),
+            ["todo(jane)"]
+        );
+        assert_eq!(masked(&["HexValues"], "#fff"), ["#fff"]);
+        assert_eq!(masked(&[r"\bjira-\d+"], "see jira-42"), ["jira-42"]);
+        assert!(IgnorePatterns::new(&["(unclosed".to_string()]).is_err());
+    }
+
+    #[test]
+    fn reports_patterns_that_backtrack_too_much() {
+        let patterns = IgnorePatterns::new(&["(a|a)+(?=b)".to_string()]).unwrap();
+
+        assert!(patterns.find_in(&"a".repeat(40)).is_err());
+    }
+
+    #[test]
+    fn masks_urls_in_large_sources() {
+        let line = "see https://example.com/docs and more ordinary words\n";
+        let text = line.repeat(20_000);
+
+        let patterns = IgnorePatterns::new(&["Urls".to_string(), "Hex".to_string()]).unwrap();
+        let found = patterns.find_in(&text).unwrap();
+
+        assert_eq!(found.len(), 20_000);
+        assert_eq!(&text[found[19_999].clone()], "https://example.com/docs");
+    }
+}
@@ -0,0 +1,281 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+use tree_sitter::Language;
+
+/// The kinds of nodes whose text is spell checked. Keywords and punctuation
+/// belong to none of them and are never checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeCategory {
+    Identifier,
+    Comment,
+    String,
+    /// Prose, such as Markdown paragraphs or text between HTML tags.
+    Text,
+}
+
+impl NodeCategory {
+    pub const ALL: [NodeCategory; 4] = [
+        NodeCategory::Identifier,
+        NodeCategory::Comment,
+        NodeCategory::String,
+        NodeCategory::Text,
+    ];
+
+    /// Maps a query capture name such as `spell.comment` to its category.
+    pub fn from_capture_name(name: &str) -> Option<NodeCategory> {
+        match name.strip_prefix("spell.")? {
+            "identifier" => Some(NodeCategory::Identifier),
+            "comment" => Some(NodeCategory::Comment),
+            "string" => Some(NodeCategory::String),
+            "text" => Some(NodeCategory::Text),
+            _ => None,
+        }
+    }
+}
+
+/// A tree-sitter grammar and the file extensions it is used for.
+#[derive(Debug)]
+pub struct LanguageDefinition {
+    /// The name used to refer to the language, e.g. in configuration.
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+    /// The language this grammar parses a part of, like Markdown for its
+    /// inline content. Such grammars are only used for regions of files of
+    /// that language, and share its configuration.
+    pub parent: Option<&'static str>,
+    /// The built-in query capturing the spell-checkable regions, see
+    /// `queries/`.
+    pub query: &'static str,
+    /// Words accepted in every file of the language, like keywords and the
+    /// names of the standard library, one per line. See `words/`.
+    pub words: &'static str,
+    /// `None` for plain text, which is checked as a whole.
+    grammar: Option<fn() -> Language>,
+}
+
+impl LanguageDefinition {
+    pub fn grammar(&self) -> Option<Language> {
+        self.grammar.map(|grammar| grammar())
+    }
+
+    /// The name the language is configured under, see `parent`.
+    pub fn config_name(&self) -> &'static str {
+        self.parent.unwrap_or(self.name)
+    }
+}
+
+/// Every language compiled into this build. Each grammar sits behind its own
+/// `lang-*` cargo feature, and plain text is always available.
+pub static LANGUAGES: &[LanguageDefinition] = &[
+    #[cfg(feature = "lang-typescript")]
+    LanguageDefinition {
+        name: "typescript",
+        extensions: &["ts", "mts", "cts"],
+        parent: None,
+        query: include_str!("../../queries/typescript.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()),
+    },
+    #[cfg(feature = "lang-typescript")]
+    LanguageDefinition {
+        name: "tsx",
+        extensions: &["tsx"],
+        parent: None,
+        query: include_str!("../../queries/tsx.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_typescript::LANGUAGE_TSX.into()),
+    },
+    #[cfg(feature = "lang-javascript")]
+    LanguageDefinition {
+        name: "javascript",
+        extensions: &["js", "mjs", "cjs", "jsx"],
+        parent: None,
+        query: include_str!("../../queries/javascript.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_javascript::LANGUAGE.into()),
+    },
+    #[cfg(feature = "lang-rust")]
+    LanguageDefinition {
+        name: "rust",
+        extensions: &["rs"],
+        parent: None,
+        query: include_str!("../../queries/rust.scm"),
+        words: include_str!("../../words/rust.txt"),
+        grammar: Some(|| tree_sitter_rust::LANGUAGE.into()),
+    },
+    #[cfg(feature = "lang-python")]
+    LanguageDefinition {
+        name: "python",
+        extensions: &["py", "pyi"],
+        parent: None,
+        query: include_str!("../../queries/python.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_python::LANGUAGE.into()),
+    },
+    #[cfg(feature = "lang-go")]
+    LanguageDefinition {
+        name: "go",
+        extensions: &["go"],
+        parent: None,
+        query: include_str!("../../queries/go.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_go::LANGUAGE.into()),
+    },
+    #[cfg(feature = "lang-java")]
+    LanguageDefinition {
+        name: "java",
+        extensions: &["java"],
+        parent: None,
+        query: include_str!("../../queries/java.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_java::LANGUAGE.into()),
+    },
+    #[cfg(feature = "lang-c")]
+    LanguageDefinition {
+        name: "c",
+        extensions: &["c", "h"],
+        parent: None,
+        query: include_str!("../../queries/c.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_c::LANGUAGE.into()),
+    },
+    #[cfg(feature = "lang-json")]
+    LanguageDefinition {
+        name: "json",
+        extensions: &["json"],
+        parent: None,
+        query: include_str!("../../queries/json.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_json::LANGUAGE.into()),
+    },
+    #[cfg(feature = "lang-toml")]
+    LanguageDefinition {
+        name: "toml",
+        extensions: &["toml"],
+        parent: None,
+        query: include_str!("../../queries/toml.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_toml_ng::LANGUAGE.into()),
+    },
+    #[cfg(feature = "lang-yaml")]
+    LanguageDefinition {
+        name: "yaml",
+        extensions: &["yaml", "yml"],
+        parent: None,
+        query: include_str!("../../queries/yaml.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_yaml::LANGUAGE.into()),
+    },
+    #[cfg(feature = "lang-markdown")]
+    LanguageDefinition {
+        name: "markdown",
+        extensions: &["md", "markdown"],
+        parent: None,
+        query: include_str!("../../queries/markdown.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_md::LANGUAGE.into()),
+    },
+    #[cfg(feature = "lang-markdown")]
+    LanguageDefinition {
+        name: "markdown_inline",
+        extensions: &[],
+        parent: Some("markdown"),
+        query: include_str!("../../queries/markdown_inline.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_md::INLINE_LANGUAGE.into()),
+    },
+    #[cfg(feature = "lang-css")]
+    LanguageDefinition {
+        name: "css",
+        extensions: &["css"],
+        parent: None,
+        query: include_str!("../../queries/css.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_css::LANGUAGE.into()),
+    },
+    #[cfg(feature = "lang-html")]
+    LanguageDefinition {
+        name: "html",
+        extensions: &["html", "htm"],
+        parent: None,
+        query: include_str!("../../queries/html.scm"),
+        words: "",
+        grammar: Some(|| tree_sitter_html::LANGUAGE.into()),
+    },
+    // No SQL grammar is available, so SQL embedded in other code is checked
+    // like plain text with its keywords accepted. `.sql` files are not
+    // claimed, as their identifiers and comments cannot be told apart.
+    LanguageDefinition {
+        name: "sql",
+        extensions: &[],
+        parent: None,
+        query: "",
+        words: include_str!("../../words/sql.txt"),
+        grammar: None,
+    },
+    LanguageDefinition {
+        name: "text",
+        extensions: &["txt"],
+        parent: None,
+        query: "",
+        words: "",
+        grammar: None,
+    },
+];
+
+pub fn by_name(name: &str) -> Option<&'static LanguageDefinition> {
+    LANGUAGES.iter().find(|language| language.name == name)
+}
+
+/// Looks up the language to parse `path` with by its extension.
+pub fn for_path(path: &Path) -> Option<&'static LanguageDefinition> {
+    for_extension(path.extension()?.to_str()?)
+}
+
+pub fn for_extension(extension: &str) -> Option<&'static LanguageDefinition> {
+    LANGUAGES
+        .iter()
+        .find(|language| language.extensions.contains(&extension))
+}
+
+/// Looks up a language by the name given to embedded code, like the info
+/// string of a Markdown code fence, which can be a name or an extension.
+pub fn for_label(label: &str) -> Option<&'static LanguageDefinition> {
+    let label = label.to_lowercase();
+
+    by_name(&label).or_else(|| for_extension(&label))
+}
+
+#[cfg(test)]
+mod test {
+    use tree_sitter::Parser;
+
+    use super::*;
+
+    #[test]
+    fn every_grammar_loads() {
+        for language in LANGUAGES {
+            if let Some(grammar) = language.grammar() {
+                Parser::new()
+                    .set_language(&grammar)
+                    .unwrap_or_else(|_| panic!("{} grammar is incompatible", language.name));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "lang-typescript", feature = "lang-rust"))]
+    fn selects_language_by_extension() {
+        let name = |path: &str| for_path(Path::new(path)).map(|language| language.name);
+
+        assert_eq!(name("src/index.ts"), Some("typescript"));
+        assert_eq!(name("src/App.tsx"), Some("tsx"));
+        assert_eq!(name("src/main.rs"), Some("rust"));
+        assert_eq!(name("notes.txt"), Some("text"));
+        assert_eq!(name("LICENSE"), None);
+        assert_eq!(name("image.png"), None);
+    }
+}
@@ -1,2 +1,7 @@
+pub mod directives;
+pub mod ignore_patterns;
+pub mod language;
 pub mod parser;
+pub mod query;
+pub mod ranges;
 pub mod word_separator;
@@ -1,57 +1,916 @@
+use std::collections::{HashMap, HashSet};
 use std::fs::read_to_string;
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use tree_sitter::{Language, Node, Parser, Tree};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Node, Parser};
+
+use super::directives::Directives;
+use super::ignore_patterns::IgnorePatterns;
+use super::language::{self, LanguageDefinition, NodeCategory};
+use super::query::SpellQuery;
+use super::ranges::{merge_ranges, overlaps_any};
+use super::word_separator::{Position, Word, extract_tokens};
+use crate::dictionary::{
+    compound::{CompoundOptions, split_compound},
+    layered::{LayeredDictionary, Lookup},
+};
+
+/// A word that was not found in the dictionary, that a layer of it forbids,
+/// or that is written in the wrong casing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownWord {
+    pub word: Word,
+    /// The tree-sitter kind of the node the word was extracted from.
+    pub node_kind: &'static str,
+    /// The language of that node, which differs from the language of the
+    /// file for embedded code.
+    pub language: &'static str,
+    pub problem: Problem,
+    /// How much the problem matters, as configured for its rule.
+    pub severity: Severity,
+    /// Likely corrections, best first.
+    pub suggestions: Vec<String>,
+}
+
+/// Why a word is reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The word is not in any dictionary.
+    Unknown,
+    Forbidden(Forbidden),
+    /// The word is in a dictionary that requires one of these casings.
+    Casing {
+        expected: Vec<String>,
+    },
+}
+
+impl Problem {
+    pub fn rule(&self) -> Rule {
+        match self {
+            Problem::Unknown => Rule::UnknownWord,
+            Problem::Forbidden(_) => Rule::ForbiddenWord,
+            Problem::Casing { .. } => Rule::Casing,
+        }
+    }
+
+    /// The corrections the problem itself implies, rather than those found
+    /// by searching the dictionary.
+    fn corrections(&self) -> Vec<String> {
+        match self {
+            Problem::Unknown => Vec::new(),
+            Problem::Forbidden(forbidden) => forbidden.replacement.iter().cloned().collect(),
+            Problem::Casing { expected } => expected.clone(),
+        }
+    }
+}
+
+/// The checks that report problems, one per kind of problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rule {
+    UnknownWord,
+    ForbiddenWord,
+    Casing,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 3] = [Rule::UnknownWord, Rule::ForbiddenWord, Rule::Casing];
+
+    /// The name of the rule in output and configuration.
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::UnknownWord => "unknown-word",
+            Rule::ForbiddenWord => "forbidden-word",
+            Rule::Casing => "casing",
+        }
+    }
+}
+
+/// How much the problems of a rule matter. Errors fail a run, warnings only
+/// once there are more than `--max-warnings` of them, and problems of rules
+/// that are off are not reported at all.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Info,
+    Warning,
+    #[default]
+    Error,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Off => "off",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// A word rejected by a forbidden dictionary layer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forbidden {
+    /// The name of the layer, e.g. `flag-words`.
+    pub layer: String,
+    pub replacement: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Where words must be written in the casing their dictionary entry
+/// requires, like "GitHub".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaseSensitivity {
+    /// Casing is never checked.
+    Off,
+    /// Comments, strings and text are checked. Identifiers are not, as their
+    /// casing follows naming conventions instead.
+    #[default]
+    Prose,
+    /// Every word is checked.
+    Strict,
+}
+
+impl CaseSensitivity {
+    fn applies_to(self, category: NodeCategory) -> bool {
+        match self {
+            CaseSensitivity::Off => false,
+            CaseSensitivity::Prose => category != NodeCategory::Identifier,
+            CaseSensitivity::Strict => true,
+        }
+    }
+}
+
+/// Options that control which parts of a file are checked.
+#[derive(Debug, Clone)]
+pub struct CheckOptions {
+    pub categories: Vec<NodeCategory>,
+    pub min_word_length: usize,
+    pub case_sensitivity: CaseSensitivity,
+    /// Categories in which unknown words split into dictionary words are
+    /// accepted.
+    pub compound_categories: Vec<NodeCategory>,
+    pub compound: CompoundOptions,
+    /// Whether code embedded in a file under a language name it gives, like
+    /// Markdown code fences, is checked with the grammar of that language.
+    pub check_code_blocks: bool,
+    pub ignore_patterns: IgnorePatterns,
+    /// The severity of each rule, `Error` unless given.
+    pub severities: HashMap<Rule, Severity>,
+}
+
+impl CheckOptions {
+    fn severity(&self, rule: Rule) -> Severity {
+        self.severities.get(&rule).copied().unwrap_or_default()
+    }
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        CheckOptions {
+            categories: NodeCategory::ALL.to_vec(),
+            min_word_length: 3,
+            case_sensitivity: CaseSensitivity::default(),
+            compound_categories: Vec::new(),
+            compound: CompoundOptions::default(),
+            check_code_blocks: false,
+            ignore_patterns: IgnorePatterns::default(),
+            severities: HashMap::new(),
+        }
+    }
+}
+
+/// How to check the files of a language, and the code of that language
+/// embedded in other files.
+pub struct Checker {
+    pub query: SpellQuery,
+    pub options: CheckOptions,
+    pub dictionary: LayeredDictionary,
+}
+
+/// The checker of every enabled language, by language name.
+pub type Checkers = HashMap<&'static str, Checker>;
+
+/// The outcome of checking a single file.
+#[derive(Debug)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub source: String,
+    pub unknown_words: Vec<UnknownWord>,
+}
+
+/// The content of the file at `path`, which must be UTF-8.
+pub fn read_source(path: &Path) -> Result<String> {
+    read_to_string(path).with_context(|| format!("Could not read {}", path.display()))
+}
+
+/// Checks `source`, the content of the file at `path`, as `language`.
+pub fn check_file(
+    path: &Path,
+    source: String,
+    language: &LanguageDefinition,
+    checkers: &Checkers,
+) -> Result<FileReport> {
+    let unknown_words = check_source(&source, language, checkers)
+        .with_context(|| format!("Could not check {} as {}", path.display(), language.name))?;
+
+    Ok(FileReport {
+        path: path.to_path_buf(),
+        source,
+        unknown_words,
+    })
+}
+
+/// How deep code can be embedded in other code, like a tagged template in a
+/// code fence, and still be checked.
+const MAX_INJECTION_DEPTH: usize = 3;
+
+/// A region of a file to check, with the checker of its language.
+struct Region<'a> {
+    checker: &'a Checker,
+    node_kind: &'static str,
+    category: NodeCategory,
+    range: Range<usize>,
+    start: Position,
+    /// How deep the code of the region is embedded, 0 for the file itself.
+    depth: usize,
+}
+
+/// What the queries of a file and of the code embedded in it capture.
+#[derive(Default)]
+struct Collected<'a> {
+    regions: Vec<Region<'a>>,
+    /// The byte ranges of every comment, checked or not, for directives.
+    comments: Vec<Range<usize>>,
+    /// The byte ranges in which no word is checked.
+    ignored: Vec<Range<usize>>,
+    /// The byte ranges of embedded code checked with its own grammar, with
+    /// its depth. Their words are only checked as that code, not as the
+    /// string around it.
+    embedded: Vec<(usize, Range<usize>)>,
+}
+
+/// Checks the words of every region of `source` captured by the query of
+/// `language` whose category is enabled, against the dictionary of that
+/// language, except where disabled by a directive in a comment. Embedded
+/// code is checked with the checker of its own language from `checkers`.
+pub fn check_source(
+    source: &str,
+    language: &LanguageDefinition,
+    checkers: &Checkers,
+) -> Result<Vec<UnknownWord>> {
+    let checker = checkers
+        .get(language.name)
+        .with_context(|| format!("{} is not enabled", language.name))?;
+
+    let mut collected = Collected::default();
+    collect(source, checker, checkers, &[], 0, &mut collected)?;
+
+    // Directives are honoured even if comments themselves are not checked.
+    collected.comments.sort_by_key(|range| range.start);
+    let directives = Directives::parse(
+        source,
+        collected
+            .comments
+            .iter()
+            .map(|range| (range.start, &source[range.clone()])),
+    );
+
+    // Patterns are matched per region, as the text between regions, like
+    // string quotes, could change what they match.
+    for region in &collected.regions {
+        let start = region.range.start;
+
+        collected.ignored.extend(
+            region
+                .checker
+                .options
+                .ignore_patterns
+                .find_in(&source[region.range.clone()])?
+                .into_iter()
+                .map(|range| start + range.start..start + range.end),
+        );
+    }
+
+    let ignored = merge_ranges(std::mem::take(&mut collected.ignored));
+
+    // The embedded ranges at each depth, indexed by depth.
+    let mut embedded = vec![Vec::new(); MAX_INJECTION_DEPTH + 1];
+    for (depth, range) in std::mem::take(&mut collected.embedded) {
+        embedded[depth].push(range);
+    }
+    let embedded = embedded.into_iter().map(merge_ranges).collect::<Vec<_>>();
+
+    let mut unknown_words = Vec::new();
+    // Regions can overlap, like a comment in Markdown prose.
+    let mut checked = HashSet::new();
+
+    for region in &collected.regions {
+        let Checker {
+            options,
+            dictionary,
+            ..
+        } = region.checker;
+
+        if !options.categories.contains(&region.category) {
+            continue;
+        }
+
+        let text = &source[region.range.clone()];
+
+        for token in extract_tokens(text, region.start, options.min_word_length) {
+            // A token listed as a whole, like "GitHub", is not split on its
+            // camel case.
+            let words = if token.parts.len() > 1
+                && dictionary.lookup(&token.word.text) != Lookup::Unknown
+            {
+                vec![token.word]
+            } else {
+                token.parts
+            };
+
+            for word in words {
+                let first = word.span.start..word.span.start + 1;
+
+                if overlaps_any(&ignored, word.span.start..word.span.end)
+                    || embedded[region.depth + 1..]
+                        .iter()
+                        .any(|ranges| overlaps_any(ranges, first.clone()))
+                    // A node of combined code can span the substitutions
+                    // between its ranges, which belong to the outer code.
+                    || region.depth > 0 && !overlaps_any(&embedded[region.depth], first)
+                    || !checked.insert((word.span.start, word.span.end))
+                {
+                    continue;
+                }
+
+                let problem = match dictionary.lookup(&word.text) {
+                    Lookup::Accepted { casings, .. } => {
+                        let original = &source[word.span.start..word.span.end];
+
+                        if !options.case_sensitivity.applies_to(region.category)
+                            || matches_casing(original, casings)
+                        {
+                            continue;
+                        }
+
+                        Problem::Casing {
+                            expected: casings.to_vec(),
+                        }
+                    }
+                    Lookup::Forbidden { layer, flag, .. } => Problem::Forbidden(Forbidden {
+                        layer: layer.to_string(),
+                        replacement: flag.and_then(|flag| flag.replacement.clone()),
+                        message: flag.and_then(|flag| flag.message.clone()),
+                    }),
+                    Lookup::Unknown
+                        if options.compound_categories.contains(&region.category)
+                            && split_compound(dictionary, &word.text, options.compound)
+                                .is_some() =>
+                    {
+                        continue;
+                    }
+                    Lookup::Unknown => Problem::Unknown,
+                };
+
+                let severity = options.severity(problem.rule());
+
+                if severity != Severity::Off && !directives.allows(&word) {
+                    unknown_words.push(UnknownWord {
+                        word,
+                        node_kind: region.node_kind,
+                        language: region.checker.query.language.config_name(),
+                        severity,
+                        suggestions: problem.corrections(),
+                        problem,
+                    });
+                }
+            }
+        }
+    }
+
+    unknown_words.sort_by_key(|unknown| unknown.word.span.start);
+
+    Ok(unknown_words)
+}
+
+/// Runs the query of `checker` over `source`, or over `ranges` of it for
+/// embedded code, and then over the code embedded in what it captures.
+fn collect<'a>(
+    source: &str,
+    checker: &'a Checker,
+    checkers: &'a Checkers,
+    ranges: &[tree_sitter::Range],
+    depth: usize,
+    collected: &mut Collected<'a>,
+) -> Result<()> {
+    let Some(grammar) = checker.query.language.grammar() else {
+        // Plain text is prose throughout, and may hold directives anywhere.
+        let regions = if ranges.is_empty() {
+            vec![(0..source.len(), Position::default())]
+        } else {
+            ranges
+                .iter()
+                .map(|range| {
+                    (
+                        range.start_byte..range.end_byte,
+                        Position {
+                            byte: range.start_byte,
+                            line: range.start_point.row,
+                            column: range.start_point.column,
+                        },
+                    )
+                })
+                .collect()
+        };
+
+        // Each line is a region of its own, so that directives and ignore
+        // patterns are matched line by line rather than over the whole file.
+        for (range, mut start) in regions {
+            for line in source[range].split_inclusive('\n') {
+                let range = start.byte..start.byte + line.len();
+
+                collected.comments.push(range.clone());
+                collected.regions.push(Region {
+                    checker,
+                    node_kind: "text",
+                    category: NodeCategory::Text,
+                    range,
+                    start,
+                    depth,
+                });
+
+                start = Position {
+                    byte: start.byte + line.len(),
+                    line: start.line + 1,
+                    column: 0,
+                };
+            }
+        }
+
+        return Ok(());
+    };
 
-pub fn parse_file(path: &PathBuf, language: &Language) -> Result<()> {
     let mut parser = Parser::new();
 
     parser
-        .set_language(language)
+        .set_language(&grammar)
         .context("Could not set language on parser")?;
 
-    let file_content = read_to_string(path).context("Could not read file")?;
+    if !ranges.is_empty() {
+        parser
+            .set_included_ranges(ranges)
+            .context("Could not restrict parser to embedded code")?;
+    }
 
     let tree = parser
-        .parse(file_content.as_bytes(), None)
+        .parse(source.as_bytes(), None)
         .context("Could not parse file")?;
 
-    traverse_tree(&tree, |node| {
-        let text = node
-            .utf8_text(file_content.as_bytes())
-            .context("Could not get file content as utf8 string")?;
-
-        println!("{:?}", text);
+    let captures = checker.query.captures(&tree, source);
 
-        Ok(())
-    })
-    .context("Could not traverse tree")?;
+    for (node, category) in captures.nodes {
+        if category == NodeCategory::Comment {
+            collected.comments.push(node.byte_range());
+        }
 
-    Ok(())
-}
+        collected.regions.push(Region {
+            checker,
+            node_kind: node.kind(),
+            category,
+            range: node.byte_range(),
+            start: node_position(&node),
+            depth,
+        });
+    }
 
-pub fn traverse_tree<F>(tree: &Tree, visit: F) -> Result<()>
-where
-    F: Fn(Node) -> Result<()>,
-{
-    let mut cursor = tree.root_node().walk();
+    collected.ignored.extend(captures.ignored);
 
-    loop {
-        visit(cursor.node())?;
+    if depth == MAX_INJECTION_DEPTH {
+        return Ok(());
+    }
 
-        if cursor.goto_first_child() {
+    for injection in captures.injections {
+        if injection.named_in_source && !checker.options.check_code_blocks {
             continue;
         }
 
-        if cursor.goto_next_sibling() {
+        // Code in languages that are unknown or disabled is not checked.
+        let Some(inner) =
+            language::for_label(&injection.label).and_then(|language| checkers.get(language.name))
+        else {
             continue;
-        }
+        };
 
-        while !cursor.goto_next_sibling() {
-            if !cursor.goto_parent() {
-                return Ok(());
-            }
+        collected.embedded.extend(
+            injection
+                .ranges
+                .iter()
+                .map(|range| (depth + 1, range.start_byte..range.end_byte)),
+        );
+
+        collect(
+            source,
+            inner,
+            checkers,
+            &injection.ranges,
+            depth + 1,
+            collected,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Whether `original` is written in one of `casings`, if there are any.
+/// Words in capitals throughout are accepted too, as in headings and
+/// constants.
+fn matches_casing(original: &str, casings: &[String]) -> bool {
+    casings.is_empty()
+        || casings.iter().any(|casing| casing == original)
+        || !original.chars().any(char::is_lowercase)
+}
+
+/// The position of the first byte of `node`, used as the origin for the spans
+/// of the words extracted from its text.
+pub fn node_position(node: &Node) -> Position {
+    let point = node.start_position();
+
+    Position {
+        byte: node.start_byte(),
+        line: point.row,
+        column: point.column,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        dictionary::{layered::Layer, word_list::WordList},
+        parsing::{language::LANGUAGES, query::QuerySources},
+    };
+
+    /// The words of `source` unknown to a dictionary of `words`, checked as
+    /// a file of the language with `extension`.
+    fn unknown_words_in(
+        extension: &str,
+        source: &str,
+        words: &[&str],
+        options: &CheckOptions,
+    ) -> Vec<String> {
+        let checkers = LANGUAGES
+            .iter()
+            .map(|language| {
+                let mut dictionary = LayeredDictionary::default();
+                dictionary.push(Layer::allow(
+                    "test",
+                    vec![Arc::new(WordList::new(
+                        "test",
+                        words.iter().map(|word| word.to_string()),
+                    ))],
+                ));
+
+                let checker = Checker {
+                    query: SpellQuery::new(language, &QuerySources::default()).unwrap(),
+                    options: options.clone(),
+                    dictionary,
+                };
+
+                (language.name, checker)
+            })
+            .collect();
+
+        let language = language::for_extension(extension).unwrap();
+
+        check_source(source, language, &checkers)
+            .unwrap()
+            .into_iter()
+            .map(|unknown| unknown.word.text)
+            .collect()
+    }
+
+    fn ignoring(patterns: &[&str]) -> CheckOptions {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .collect::<Vec<_>>();
+
+        CheckOptions {
+            ignore_patterns: IgnorePatterns::new(&patterns).unwrap(),
+            ..CheckOptions::default()
         }
     }
+
+    #[cfg(feature = "lang-typescript")]
+    fn unknown_words(source: &str, words: &[&str]) -> Vec<String> {
+        unknown_words_with(source, words, &CheckOptions::default())
+    }
+
+    #[cfg(feature = "lang-typescript")]
+    fn unknown_words_with(source: &str, words: &[&str], options: &CheckOptions) -> Vec<String> {
+        unknown_words_in("ts", source, words, options)
+    }
+
+    #[test]
+    #[cfg(feature = "lang-typescript")]
+    fn reports_words_missing_from_dictionary() {
+        assert_eq!(
+            unknown_words(
+                "function gretDoom() { log(\"Helo, wrlod!\"); }",
+                &["doom", "log"]
+            ),
+            ["gret", "helo", "wrlod"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "lang-typescript")]
+    fn checks_each_word_once() {
+        assert_eq!(
+            unknown_words("const value = { key: value };", &["key"]),
+            ["value", "value"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "lang-typescript")]
+    fn honours_directives() {
+        assert_eq!(
+            unknown_words(
+                "// rspell:ignore wrlod\nlet helo = wrlod;\n// rspell:disable-next-line\nlet foo = barr;",
+                &["let"]
+            ),
+            ["helo"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "lang-typescript")]
+    fn checks_casing_in_prose_only() {
+        assert_eq!(
+            unknown_words(
+                "// Hosted on github and GitHub, see GITHUB.\nconst github = 1;",
+                &["hosted", "and", "GitHub", "see", "const"]
+            ),
+            ["github"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "lang-typescript")]
+    fn checks_casing_everywhere_if_strict() {
+        let options = CheckOptions {
+            case_sensitivity: CaseSensitivity::Strict,
+            ..CheckOptions::default()
+        };
+
+        assert_eq!(
+            unknown_words_with("const github = 1;", &["const", "GitHub"], &options),
+            ["github"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "lang-typescript")]
+    fn accepts_compound_words_in_enabled_categories() {
+        let options = CheckOptions {
+            compound_categories: vec![NodeCategory::Identifier],
+            ..CheckOptions::default()
+        };
+
+        assert_eq!(
+            unknown_words_with(
+                "// readfile\nconst readfile = keyvalue;",
+                &["const", "read", "file", "key"],
+                &options
+            ),
+            ["readfile", "keyvalue"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "lang-typescript")]
+    fn skips_disabled_categories() {
+        let options = CheckOptions {
+            categories: vec![NodeCategory::Comment],
+            ..CheckOptions::default()
+        };
+
+        assert_eq!(
+            unknown_words_with(
+                "// a commnet\nconst identifer = \"strign\";",
+                &["a"],
+                &options
+            ),
+            ["commnet"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "lang-typescript")]
+    fn masks_ignore_patterns() {
+        assert_eq!(
+            unknown_words_with(
+                "// See commit 3f2a9c1e for id 123e4567-e89b-12d3-a456-426614174000, not deadbeeff.",
+                &["see", "commit", "for", "not"],
+                &ignoring(&["Hex", "Uuid"])
+            ),
+            ["deadbeeff"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "lang-markdown")]
+    fn checks_markdown_prose_only() {
+        let source = "\
+# Installng
+
+Run `cargo instal` or see [the gide](https://exmple.com/docz) and \
+<https://exmple.org>, also www.exmple.net/pth.
+
+- a lisst item <span class=\"hiden\">with tagz</span>
+
+![an imaeg](pics/foto.png)
+
+```rust
+fn mian() {}
+```
+";
+
+        assert_eq!(
+            unknown_words_in(
+                "md",
+                source,
+                &["run", "see", "the", "and", "also", "item", "with", "an"],
+                &ignoring(&["Urls"])
+            ),
+            ["installng", "gide", "lisst", "tagz", "imaeg"]
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "lang-markdown", feature = "lang-rust"))]
+    fn checks_code_blocks_if_enabled() {
+        let source = "Some text.\n\n```rust\nfn mian() {}\n```\n";
+        let options = CheckOptions {
+            check_code_blocks: true,
+            ..CheckOptions::default()
+        };
+
+        assert_eq!(
+            unknown_words_in("md", source, &["some", "text", "fn"], &options),
+            ["mian"]
+        );
+    }
+
+    #[test]
+    fn names_files_that_cannot_be_read() {
+        let path = std::env::temp_dir().join(format!("rspell-latin1-{}.txt", std::process::id()));
+        std::fs::write(&path, b"caf\xe9\n").unwrap();
+
+        let error = read_source(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(error.to_string().contains(&*path.to_string_lossy()));
+    }
+
+    #[test]
+    fn checks_plain_text() {
+        let source = "Plain txet, see https://exmple.com.\nrspell:ignore wrod\nA wrod.\n";
+
+        assert_eq!(
+            unknown_words_in("txt", source, &["plain", "see"], &ignoring(&["Urls"])),
+            ["txet"]
+        );
+    }
+
+    #[test]
+    fn checks_large_plain_text_files() {
+        let source = format!(
+            "{}A last txet, see https://exmple.com.\n",
+            "Plain text, see https://example.com/docs.\n".repeat(25_000)
+        );
+
+        assert_eq!(
+            unknown_words_in(
+                "txt",
+                &source,
+                &["plain", "text", "see", "last"],
+                &ignoring(&["Urls"])
+            ),
+            ["txet"]
+        );
+    }
+
+    #[test]
+    fn drops_problems_of_rules_that_are_off() {
+        let options = CheckOptions {
+            severities: HashMap::from([(Rule::UnknownWord, Severity::Off)]),
+            ..CheckOptions::default()
+        };
+
+        assert_eq!(
+            unknown_words_in("txt", "Hosted on github, not gthub.", &["GitHub"], &options),
+            ["github"]
+        );
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "lang-typescript",
+        feature = "lang-css",
+        feature = "lang-html",
+        feature = "lang-json"
+    ))]
+    fn checks_embedded_code_with_its_own_grammar() {
+        let source = "\
+const style = css`.buton { colr: red; }`;
+const page = html`<p class=\"mian\">Helo</p>`;
+const query = sql`select nmae from users`;
+const data = /* json */ '{\"key\": \"valeu\"}';
+";
+
+        // Words of embedded code are checked once, as that code rather than
+        // as part of the string.
+        assert_eq!(
+            unknown_words_in(
+                "ts",
+                source,
+                &[
+                    "const", "style", "css", "red", "page", "html", "class", "query", "sql",
+                    "select", "from", "json", "users", "data", "key"
+                ],
+                &CheckOptions::default()
+            ),
+            ["buton", "colr", "mian", "helo", "nmae", "valeu"]
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "lang-typescript", feature = "lang-css"))]
+    fn checks_the_fragments_of_a_template_as_one() {
+        let source = "const style = css`.buton { color: ${shdae}; colr: red; }`;\n";
+
+        // The fragments around the substitution are parsed together, and the
+        // substitution is checked as TypeScript.
+        assert_eq!(
+            unknown_words_in(
+                "ts",
+                source,
+                &["const", "style", "css", "color", "red"],
+                &CheckOptions::default()
+            ),
+            ["buton", "shdae", "colr"]
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "lang-typescript", feature = "lang-markdown"))]
+    fn maps_spans_of_code_blocks_to_the_file() {
+        let mut dictionary = LayeredDictionary::default();
+        dictionary.push(Layer::allow(
+            "test",
+            vec![Arc::new(WordList::new("test", ["const".to_string()]))],
+        ));
+        let checkers = LANGUAGES
+            .iter()
+            .map(|language| {
+                let checker = Checker {
+                    query: SpellQuery::new(language, &QuerySources::default()).unwrap(),
+                    options: CheckOptions {
+                        check_code_blocks: true,
+                        ..CheckOptions::default()
+                    },
+                    dictionary: dictionary.clone(),
+                };
+
+                (language.name, checker)
+            })
+            .collect();
+
+        let source = "```ts\nconst valeu = 1;\n```\n";
+        let unknown =
+            check_source(source, language::for_extension("md").unwrap(), &checkers).unwrap();
+
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].node_kind, "identifier");
+        assert_eq!(
+            (
+                unknown[0].word.span.start,
+                unknown[0].word.span.line,
+                unknown[0].word.span.column
+            ),
+            (12, 1, 6)
+        );
+    }
 }
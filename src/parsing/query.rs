@@ -0,0 +1,329 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    ops::Range,
+    path::Path,
+};
+
+use anyhow::{Context, Result, bail};
+use tree_sitter::{Node, Query, QueryCursor, StreamingIterator, Tree};
+
+use super::language::{LanguageDefinition, NodeCategory};
+
+/// Where to find user-provided queries. A file named `<language>.scm` in
+/// `dir`, e.g. `typescript.scm`, is added to the built-in query for that
+/// language, or used instead of it if `replace_builtin` is set.
+#[derive(Debug, Clone, Default)]
+pub struct QuerySources<'a> {
+    pub dir: Option<&'a Path>,
+    pub replace_builtin: bool,
+}
+
+/// The capture marking text that is never checked, even if another pattern
+/// captures it, e.g. the identifiers of Rust lifetimes.
+const IGNORE_CAPTURE: &str = "spell.ignore";
+
+/// The captures and property marking code in another language, following
+/// the `injections.scm` convention of tree-sitter: the language is set with
+/// `#set! injection.language` or captured from the file, and the contents a
+/// pattern with `#set! injection.combined` captures in one node are parsed
+/// together.
+const INJECTION_CONTENT_CAPTURE: &str = "injection.content";
+const INJECTION_LANGUAGE: &str = "injection.language";
+const INJECTION_COMBINED: &str = "injection.combined";
+
+/// A compiled tree-sitter query whose `@spell.*` captures mark the regions
+/// of a file to check.
+#[derive(Debug)]
+pub struct SpellQuery {
+    pub language: &'static LanguageDefinition,
+    /// `None` for plain text, which has no grammar to query.
+    query: Option<Query>,
+    /// The category of each capture, indexed by capture index. Captures
+    /// outside the `spell.` namespace are only used by predicates.
+    categories: Vec<Option<NodeCategory>>,
+    /// The index of the `@spell.ignore` capture, if the query uses it.
+    ignore: Option<u32>,
+    injection_content: Option<u32>,
+    injection_language: Option<u32>,
+}
+
+/// What a [`SpellQuery`] found in a parsed file.
+#[derive(Debug, Default)]
+pub struct Captures<'tree> {
+    /// Every captured node in document order, with its category. A node
+    /// captured by several patterns is only returned once.
+    pub nodes: Vec<(Node<'tree>, NodeCategory)>,
+    /// The byte ranges captured as `@spell.ignore`.
+    pub ignored: Vec<Range<usize>>,
+    pub injections: Vec<Injection>,
+}
+
+/// A region of a file written in another language, like the inline content
+/// of Markdown or a fenced code block.
+#[derive(Debug, Clone)]
+pub struct Injection {
+    /// The language, as a name or an extension.
+    pub label: String,
+    /// The ranges of the code, in document order. A combined injection,
+    /// like a tagged template with substitutions, has one per fragment.
+    pub ranges: Vec<tree_sitter::Range>,
+    /// Whether the file names the language, like the info string of a code
+    /// fence, rather than the query.
+    pub named_in_source: bool,
+}
+
+impl SpellQuery {
+    pub fn new(language: &'static LanguageDefinition, sources: &QuerySources) -> Result<Self> {
+        let mut source = String::new();
+
+        if !sources.replace_builtin {
+            source.push_str(language.query);
+        }
+
+        if let Some(dir) = sources.dir {
+            let path = dir.join(format!("{}.scm", language.name));
+
+            if path.exists() {
+                let user_query = fs::read_to_string(&path)
+                    .with_context(|| format!("Could not read query {}", path.display()))?;
+
+                source.push('\n');
+                source.push_str(&user_query);
+            }
+        }
+
+        Self::from_source(language, &source)
+    }
+
+    pub fn from_source(language: &'static LanguageDefinition, source: &str) -> Result<Self> {
+        let Some(grammar) = language.grammar() else {
+            if !source.trim().is_empty() {
+                bail!("{} has no grammar to query", language.name);
+            }
+
+            return Ok(SpellQuery {
+                language,
+                query: None,
+                categories: Vec::new(),
+                ignore: None,
+                injection_content: None,
+                injection_language: None,
+            });
+        };
+
+        let query = Query::new(&grammar, source)
+            .with_context(|| format!("Invalid {} query", language.name))?;
+
+        let categories = query
+            .capture_names()
+            .iter()
+            .map(|name| match NodeCategory::from_capture_name(name) {
+                None if name.starts_with("spell.") && *name != IGNORE_CAPTURE => {
+                    bail!("Unknown capture @{name} in {} query", language.name)
+                }
+                category => Ok(category),
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(SpellQuery {
+            language,
+            ignore: query.capture_index_for_name(IGNORE_CAPTURE),
+            injection_content: query.capture_index_for_name(INJECTION_CONTENT_CAPTURE),
+            injection_language: query.capture_index_for_name(INJECTION_LANGUAGE),
+            query: Some(query),
+            categories,
+        })
+    }
+
+    /// Runs the query over `tree`, which must have been parsed with the
+    /// grammar of the language.
+    pub fn captures<'tree>(&self, tree: &'tree Tree, source: &str) -> Captures<'tree> {
+        let mut result = Captures::default();
+
+        let Some(query) = &self.query else {
+            return result;
+        };
+
+        let mut cursor = QueryCursor::new();
+        let mut captures = cursor.captures(query, tree.root_node(), source.as_bytes());
+
+        let mut seen = HashSet::new();
+        // The index in `result.injections` of each combined injection, by
+        // pattern and the node holding its fragments.
+        let mut combined = HashMap::<_, usize>::new();
+
+        while let Some((query_match, index)) = captures.next() {
+            let capture = query_match.captures[*index];
+
+            if Some(capture.index) == self.ignore {
+                result.ignored.push(capture.node.byte_range());
+            } else if Some(capture.index) == self.injection_content {
+                let properties = query.property_settings(query_match.pattern_index);
+                let fixed = properties
+                    .iter()
+                    .find(|property| &*property.key == INJECTION_LANGUAGE)
+                    .and_then(|property| property.value.as_deref());
+
+                let named = query_match
+                    .captures
+                    .iter()
+                    .find(|capture| Some(capture.index) == self.injection_language)
+                    .map(|capture| &source[capture.node.byte_range()]);
+
+                let Some(label) = fixed.or(named) else {
+                    continue;
+                };
+
+                let key = properties
+                    .iter()
+                    .any(|property| &*property.key == INJECTION_COMBINED)
+                    .then(|| {
+                        let parent = capture.node.parent().map(|parent| parent.id());
+                        (query_match.pattern_index, parent)
+                    });
+
+                match key.and_then(|key| combined.get(&key)) {
+                    Some(&index) => {
+                        let injection = &mut result.injections[index];
+                        if !injection.ranges.contains(&capture.node.range()) {
+                            injection.ranges.push(capture.node.range());
+                        }
+                    }
+                    None => {
+                        if let Some(key) = key {
+                            combined.insert(key, result.injections.len());
+                        }
+
+                        result.injections.push(Injection {
+                            label: label.trim().to_string(),
+                            ranges: vec![capture.node.range()],
+                            named_in_source: fixed.is_none(),
+                        });
+                    }
+                }
+            } else if let Some(category) = self.categories[capture.index as usize]
+                && seen.insert(capture.node.id())
+            {
+                result.nodes.push((capture.node, category));
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parsing::language::LANGUAGES;
+
+    #[test]
+    fn builtin_queries_compile() {
+        for language in LANGUAGES {
+            SpellQuery::new(language, &QuerySources::default())
+                .unwrap_or_else(|error| panic!("{error:?}"));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "lang-typescript")]
+    fn rejects_unknown_spell_captures() {
+        let language = crate::parsing::language::for_path(Path::new("test.ts")).unwrap();
+
+        assert!(SpellQuery::from_source(language, "(comment) @spell.commment").is_err());
+        assert!(SpellQuery::from_source(language, "(comment) @other").is_ok());
+        assert!(SpellQuery::from_source(language, "(comment) @spell.ignore").is_ok());
+    }
+
+    #[cfg(any(
+        feature = "lang-typescript",
+        feature = "lang-rust",
+        feature = "lang-markdown"
+    ))]
+    fn parse(query: &SpellQuery, source: &str) -> Tree {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&query.language.grammar().unwrap())
+            .unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    /// The text of every node `query` captures in `source`, leaving out
+    /// ignored nodes.
+    #[cfg(any(feature = "lang-typescript", feature = "lang-rust"))]
+    fn captured<'a>(query: &SpellQuery, source: &'a str) -> Vec<(&'a str, NodeCategory)> {
+        let tree = parse(query, source);
+        let captures = query.captures(&tree, source);
+
+        captures
+            .nodes
+            .into_iter()
+            .filter(|(node, _)| !captures.ignored.contains(&node.byte_range()))
+            .map(|(node, category)| (&source[node.byte_range()], category))
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "lang-typescript")]
+    fn drops_ignored_nodes() {
+        let language = crate::parsing::language::for_path(Path::new("test.ts")).unwrap();
+        let query = SpellQuery::from_source(
+            language,
+            "(identifier) @spell.identifier (call_expression function: (identifier) @spell.ignore)",
+        )
+        .unwrap();
+
+        assert_eq!(
+            captured(&query, "let value = compute();"),
+            [("value", NodeCategory::Identifier)]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "lang-rust")]
+    fn captures_rust_sources() {
+        let language = crate::parsing::language::for_path(Path::new("test.rs")).unwrap();
+        let query = SpellQuery::new(language, &QuerySources::default()).unwrap();
+
+        let source = r##"
+//! Crate docs.
+#[derive(Debug)]
+#[serde(rename_all = "kebab-case")]
+struct Holder<'long> {
+    /// Field docs.
+    text: &'long str,
+}
+
+macro_rules! shout {
+    ($message:expr) => { println!("{}!", $message) };
+}
+
+fn main() {
+    let raw = r#"raw text"#;
+    shout!("loud text");
+}
+"##;
+
+        assert_eq!(
+            captured(&query, source),
+            [
+                ("//! Crate docs.\n", NodeCategory::Comment),
+                ("kebab-case", NodeCategory::String),
+                ("Holder", NodeCategory::Identifier),
+                ("/// Field docs.\n", NodeCategory::Comment),
+                ("text", NodeCategory::Identifier),
+                ("shout", NodeCategory::Identifier),
+                ("$message", NodeCategory::Identifier),
+                ("println", NodeCategory::Identifier),
+                ("{}!", NodeCategory::String),
+                ("$message", NodeCategory::Identifier),
+                ("main", NodeCategory::Identifier),
+                ("raw", NodeCategory::Identifier),
+                ("raw text", NodeCategory::String),
+                ("shout", NodeCategory::Identifier),
+                ("loud text", NodeCategory::String),
+            ]
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Sorted, disjoint byte ranges, searched in logarithmic time for the
+//! ranges a word falls in.
+
+use std::ops::Range;
+
+/// `ranges` sorted, with those that overlap or touch merged into one.
+pub fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged = Vec::<Range<usize>>::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+
+    merged
+}
+
+/// Whether `range` overlaps any of `ranges`, which must be sorted and
+/// disjoint, as returned by `merge_ranges`.
+pub fn overlaps_any(ranges: &[Range<usize>], range: Range<usize>) -> bool {
+    let index = ranges.partition_point(|other| other.end <= range.start);
+
+    ranges
+        .get(index)
+        .is_some_and(|other| other.start < range.end)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn finds_overlaps_with_merged_ranges() {
+        let ranges = merge_ranges(vec![10..20, 0..5, 15..25, 5..8]);
+        assert_eq!(ranges, [0..8, 10..25]);
+
+        assert!(overlaps_any(&ranges, 7..9));
+        assert!(overlaps_any(&ranges, 24..30));
+        assert!(!overlaps_any(&ranges, 8..10));
+        assert!(!overlaps_any(&ranges, 25..30));
+    }
+}
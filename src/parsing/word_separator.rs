@@ -2,14 +2,131 @@ use fancy_regex::Regex;
 use once_cell::sync::Lazy;
 use unicode_segmentation::UnicodeSegmentation;
 
-pub fn extract_words(text: &str) -> impl Iterator<Item = String> {
+/// A location in a source file. `line` and `column` are zero-based, and the
+/// column is counted in bytes, matching `tree_sitter::Point`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Position {
+    pub byte: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// The byte range of a word in its source file, together with the line and
+/// column it starts on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A single word produced by [`extract_tokens`], lowercased for dictionary
+/// lookup. The original casing can be recovered by slicing the source with
+/// `span`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    pub text: String,
+    pub span: Span,
+}
+
+/// A piece of text as split before its camel case, e.g. "GitHub", with the
+/// words it splits into. Dictionaries can list a whole token, so it is looked
+/// up before its parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub word: Word,
+    pub parts: Vec<Word>,
+}
+
+/// Splits `text` into tokens of at least `min_length` bytes, where `origin`
+/// is the position of the first byte of `text` in its source file.
+pub fn extract_tokens(
+    text: &str,
+    origin: Position,
+    min_length: usize,
+) -> impl Iterator<Item = Token> {
+    let mut locator = Locator::new(text, origin);
+
     text.split_whitespace()
         .flat_map(split_on_numbers)
         .flat_map(split_unicode_word_boundary)
         .flat_map(split_snake_case)
-        .flat_map(split_camel_case)
-        .map(|s| s.to_lowercase())
-        .filter(|str| str.len() > 2)
+        .filter(move |str| str.len() >= min_length)
+        .map(move |str| Token {
+            word: locator.word(str),
+            parts: split_camel_case(str)
+                .filter(|part| part.len() >= min_length)
+                .map(|part| locator.word(part))
+                .collect(),
+        })
+}
+
+/// The words of every token in `text`, see [`extract_tokens`].
+#[cfg(test)]
+pub fn extract_words(
+    text: &str,
+    origin: Position,
+    min_length: usize,
+) -> impl Iterator<Item = Word> {
+    extract_tokens(text, origin, min_length).flat_map(|token| token.parts)
+}
+
+/// Maps sub-slices of a text back to spans in the source file. The splitters
+/// only ever yield slices of their input, in order, so the offset of a piece
+/// is its distance from the start of the text.
+struct Locator<'a> {
+    text: &'a str,
+    origin: Position,
+    scanned: usize,
+    line: usize,
+    line_start: Option<usize>,
+}
+
+impl<'a> Locator<'a> {
+    fn new(text: &'a str, origin: Position) -> Self {
+        Locator {
+            text,
+            origin,
+            scanned: 0,
+            line: origin.line,
+            line_start: None,
+        }
+    }
+
+    fn word(&mut self, piece: &str) -> Word {
+        Word {
+            text: piece.to_lowercase(),
+            span: self.span_of(piece),
+        }
+    }
+
+    fn span_of(&mut self, piece: &str) -> Span {
+        let offset = piece.as_ptr() as usize - self.text.as_ptr() as usize;
+
+        for (index, byte) in self.text.as_bytes()[self.scanned..offset]
+            .iter()
+            .enumerate()
+        {
+            if *byte == b'\n' {
+                self.line += 1;
+                self.line_start = Some(self.scanned + index + 1);
+            }
+        }
+        self.scanned = offset;
+
+        let column = match self.line_start {
+            Some(line_start) => offset - line_start,
+            None => self.origin.column + offset,
+        };
+
+        Span {
+            start: self.origin.byte + offset,
+            end: self.origin.byte + offset + piece.len(),
+            line: self.line,
+            column,
+        }
+    }
 }
 
 fn split_unicode_word_boundary(text: &str) -> impl Iterator<Item = &str> {
@@ -37,51 +154,120 @@ fn split_camel_case(text: &str) -> impl Iterator<Item = &str> {
 mod test {
     use super::*;
 
+    fn words(text: &str) -> Vec<String> {
+        extract_words(text, Position::default(), 3)
+            .map(|word| word.text)
+            .collect()
+    }
+
     #[test]
     fn test_split_white_space() {
-        assert_eq!(
-            extract_words("hello world").collect::<Vec<_>>(),
-            ["hello", "world"]
-        );
+        assert_eq!(words("hello world"), ["hello", "world"]);
     }
 
     #[test]
     fn test_split_snake_case() {
-        assert_eq!(
-            extract_words("hello_world_test").collect::<Vec<_>>(),
-            ["hello", "world", "test"]
-        );
+        assert_eq!(words("hello_world_test"), ["hello", "world", "test"]);
     }
 
     #[test]
     fn test_split_on_numbers() {
-        assert_eq!(
-            extract_words("hello2world").collect::<Vec<_>>(),
-            ["hello", "world"]
-        );
+        assert_eq!(words("hello2world"), ["hello", "world"]);
     }
 
     #[test]
     fn test_split_camel_case() {
-        assert_eq!(
-            extract_words("camelCaseTest").collect::<Vec<_>>(),
-            ["camel", "case", "test"]
-        );
+        assert_eq!(words("camelCaseTest"), ["camel", "case", "test"]);
     }
 
     #[test]
     fn test_function_definition() {
         assert_eq!(
-            extract_words("function parseJson(text: string)").collect::<Vec<_>>(),
+            words("function parseJson(text: string)"),
             ["function", "parse", "json", "text", "string"]
         );
     }
 
     #[test]
     fn discards_short_words_after_parsing() {
+        assert_eq!(words("fn isTheCatInTheDog"), ["the", "cat", "the", "dog"])
+    }
+
+    #[test]
+    fn keeps_tokens_whole_alongside_their_parts() {
+        let tokens = extract_tokens("GitHub_api", Position::default(), 3)
+            .map(|token| {
+                (
+                    token.word.text,
+                    token.parts.into_iter().map(|part| part.text).collect(),
+                )
+            })
+            .collect::<Vec<(String, Vec<String>)>>();
+
+        assert_eq!(
+            tokens,
+            [
+                (
+                    "github".to_string(),
+                    vec!["git".to_string(), "hub".to_string()]
+                ),
+                ("api".to_string(), vec!["api".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn tracks_spans_through_splitting() {
+        let spans = extract_words("let fooBar_baz2qux", Position::default(), 3)
+            .map(|word| (word.text, word.span.start, word.span.end))
+            .collect::<Vec<_>>();
+
         assert_eq!(
-            extract_words("fn isTheCatInTheDog").collect::<Vec<_>>(),
-            ["the", "cat", "the", "dog"]
-        )
+            spans,
+            [
+                ("let".to_string(), 0, 3),
+                ("foo".to_string(), 4, 7),
+                ("bar".to_string(), 7, 10),
+                ("baz".to_string(), 11, 14),
+                ("qux".to_string(), 15, 18),
+            ]
+        );
+    }
+
+    #[test]
+    fn offsets_spans_by_origin() {
+        let origin = Position {
+            byte: 100,
+            line: 4,
+            column: 8,
+        };
+
+        let spans = extract_words("hello\n  wörld again", origin, 3)
+            .map(|word| word.span)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            spans,
+            [
+                Span {
+                    start: 100,
+                    end: 105,
+                    line: 4,
+                    column: 8
+                },
+                Span {
+                    start: 108,
+                    end: 114,
+                    line: 5,
+                    column: 2
+                },
+                Span {
+                    start: 115,
+                    end: 120,
+                    line: 5,
+                    column: 9
+                },
+            ]
+        );
     }
 }
@@ -0,0 +1,82 @@
+//! Checkstyle XML, read by many CI servers and code review tools. Every
+//! checked file gets a `<file>` element, with an `<error>` per issue.
+
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::{Reporter, Summary, column, describe, escape_xml};
+use crate::parsing::parser::FileReport;
+
+pub struct Checkstyle;
+
+impl Reporter for Checkstyle {
+    fn write(&self, writer: &mut dyn Write, reports: &[FileReport], _: &Summary) -> Result<()> {
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(writer, r#"<checkstyle version="4.3">"#)?;
+
+        for report in reports {
+            let name = escape_xml(&report.path.display().to_string());
+
+            if report.unknown_words.is_empty() {
+                writeln!(writer, r#"  <file name="{name}"/>"#)?;
+                continue;
+            }
+
+            writeln!(writer, r#"  <file name="{name}">"#)?;
+
+            for unknown in &report.unknown_words {
+                writeln!(
+                    writer,
+                    r#"    <error line="{}" column="{}" severity="{}" message="{}" source="rspell.{}"/>"#,
+                    unknown.word.span.line + 1,
+                    column(report, unknown),
+                    unknown.severity.name(),
+                    escape_xml(&describe(report, unknown)),
+                    unknown.problem.rule().name(),
+                )?;
+            }
+
+            writeln!(writer, "  </file>")?;
+        }
+
+        writeln!(writer, "</checkstyle>")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::reporting::test::report;
+
+    #[test]
+    fn writes_an_error_per_issue() {
+        let clean = FileReport {
+            path: PathBuf::from("src/clean.ts"),
+            source: String::new(),
+            unknown_words: Vec::new(),
+        };
+
+        let mut output = Vec::new();
+        Checkstyle
+            .write(&mut output, &[report(), clean], &Summary::default())
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<checkstyle version="4.3">
+  <file name="src/typo.ts">
+    <error line="2" column="10" severity="warning" message="Unknown word &quot;wrlod&quot;, did you mean: world, word?" source="rspell.unknown-word"/>
+    <error line="2" column="18" severity="error" message="Forbidden word &quot;blacklist&quot;: Use inclusive language, use &quot;denylist&quot; instead" source="rspell.forbidden-word"/>
+  </file>
+  <file name="src/clean.ts"/>
+</checkstyle>
+"#
+        );
+    }
+}
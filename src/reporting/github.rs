@@ -0,0 +1,85 @@
+//! GitHub Actions workflow commands, which GitHub shows as annotations on
+//! the lines of a pull request.
+
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::{Reporter, Summary, column, describe};
+use crate::parsing::parser::{FileReport, Severity};
+
+pub struct Github;
+
+impl Reporter for Github {
+    fn write(&self, writer: &mut dyn Write, reports: &[FileReport], _: &Summary) -> Result<()> {
+        for report in reports {
+            let file = escape_property(&report.path.display().to_string());
+
+            for unknown in &report.unknown_words {
+                // Both columns are inclusive.
+                let start = column(report, unknown);
+                let length = report.source[unknown.word.span.start..unknown.word.span.end]
+                    .chars()
+                    .count();
+
+                let command = match unknown.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Info | Severity::Off => "notice",
+                };
+
+                writeln!(
+                    writer,
+                    "::{command} file={file},line={},col={start},endColumn={},title={}::{}",
+                    unknown.word.span.line + 1,
+                    start + length - 1,
+                    escape_property(&format!("rspell ({})", unknown.problem.rule().name())),
+                    escape_data(&describe(report, unknown)),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Escapes the message of a workflow command.
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a property of a workflow command, which additionally ends at `,`
+/// and `::`.
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::reporting::test::report;
+
+    #[test]
+    fn writes_a_workflow_command_per_issue() {
+        let mut output = Vec::new();
+        Github
+            .write(&mut output, &[report()], &Summary::default())
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "::warning file=src/typo.ts,line=2,col=10,endColumn=14,title=rspell (unknown-word)\
+             ::Unknown word \"wrlod\", did you mean: world, word?\n\
+             ::error file=src/typo.ts,line=2,col=18,endColumn=26,title=rspell (forbidden-word)\
+             ::Forbidden word \"blacklist\": Use inclusive language, use \"denylist\" instead\n"
+        );
+    }
+
+    #[test]
+    fn escapes_properties_and_data() {
+        assert_eq!(escape_data("50%\nof"), "50%25%0Aof");
+        assert_eq!(escape_property("a:b,c"), "a%3Ab%2Cc");
+    }
+}
@@ -0,0 +1,289 @@
+//! Machine-readable output as a single JSON document or as newline-delimited
+//! JSON. The shape of both is versioned by `SCHEMA_VERSION`, which changes
+//! whenever a field is renamed, removed or changes its meaning.
+
+use std::{collections::BTreeMap, io::Write};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::{Reporter, Summary, column, count};
+use crate::parsing::parser::{FileReport, Problem, Rule, Severity, UnknownWord};
+
+/// 2 counts columns in characters rather than bytes.
+pub const SCHEMA_VERSION: u32 = 2;
+
+#[derive(Serialize)]
+struct Document<'a> {
+    version: u32,
+    issues: Vec<Issue<'a>>,
+    summary: SummaryRecord,
+}
+
+/// A line of NDJSON output, tagged with its `type`.
+#[derive(Serialize)]
+struct Line<'a> {
+    version: u32,
+    #[serde(flatten)]
+    record: Record<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Record<'a> {
+    Issue(Issue<'a>),
+    Summary(SummaryRecord),
+}
+
+#[derive(Serialize)]
+struct Issue<'a> {
+    file: String,
+    /// The language of the code the word is in, which can be embedded in a
+    /// file of another language.
+    language: &'a str,
+    rule: Rule,
+    severity: Severity,
+    /// The word as written in the file.
+    word: &'a str,
+    /// The word as looked up in the dictionaries.
+    normalized: &'a str,
+    /// Byte offsets of the word in the file.
+    start: usize,
+    end: usize,
+    /// One-based, with the column counted in characters.
+    line: usize,
+    column: usize,
+    node_kind: &'a str,
+    suggestions: &'a [String],
+    /// The dictionary layer forbidding the word.
+    layer: Option<&'a str>,
+    replacement: Option<&'a str>,
+    message: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct SummaryRecord {
+    files: usize,
+    files_with_issues: usize,
+    issues: usize,
+    errors: usize,
+    warnings: usize,
+    /// The number of issues of every rule, including those without any.
+    rules: BTreeMap<Rule, usize>,
+    timings: Timings,
+}
+
+/// Durations in milliseconds.
+#[derive(Serialize)]
+struct Timings {
+    check: f64,
+    suggest: f64,
+}
+
+impl<'a> Issue<'a> {
+    fn new(report: &'a FileReport, unknown: &'a UnknownWord) -> Self {
+        let span = unknown.word.span;
+        let forbidden = match &unknown.problem {
+            Problem::Forbidden(forbidden) => Some(forbidden),
+            _ => None,
+        };
+
+        Issue {
+            file: report.path.display().to_string(),
+            language: unknown.language,
+            rule: unknown.problem.rule(),
+            severity: unknown.severity,
+            word: &report.source[span.start..span.end],
+            normalized: &unknown.word.text,
+            start: span.start,
+            end: span.end,
+            line: span.line + 1,
+            column: column(report, unknown),
+            node_kind: unknown.node_kind,
+            suggestions: &unknown.suggestions,
+            layer: forbidden.map(|forbidden| forbidden.layer.as_str()),
+            replacement: forbidden.and_then(|forbidden| forbidden.replacement.as_deref()),
+            message: forbidden.and_then(|forbidden| forbidden.message.as_deref()),
+        }
+    }
+}
+
+impl SummaryRecord {
+    fn new(reports: &[FileReport], summary: &Summary) -> Self {
+        let mut rules = Rule::ALL
+            .into_iter()
+            .map(|rule| (rule, 0))
+            .collect::<BTreeMap<_, _>>();
+
+        for unknown in reports.iter().flat_map(|report| &report.unknown_words) {
+            *rules.entry(unknown.problem.rule()).or_default() += 1;
+        }
+
+        SummaryRecord {
+            files: summary.files,
+            files_with_issues: reports
+                .iter()
+                .filter(|report| !report.unknown_words.is_empty())
+                .count(),
+            issues: rules.values().sum(),
+            errors: count(reports, Severity::Error),
+            warnings: count(reports, Severity::Warning),
+            rules,
+            timings: Timings {
+                check: summary.check_time.as_secs_f64() * 1000.0,
+                suggest: summary.suggest_time.as_secs_f64() * 1000.0,
+            },
+        }
+    }
+}
+
+fn issues(reports: &[FileReport]) -> impl Iterator<Item = Issue<'_>> {
+    reports.iter().flat_map(|report| {
+        report
+            .unknown_words
+            .iter()
+            .map(move |unknown| Issue::new(report, unknown))
+    })
+}
+
+/// A single JSON document.
+pub struct Json;
+
+/// One JSON object per line.
+pub struct Ndjson;
+
+impl Reporter for Json {
+    fn write(
+        &self,
+        writer: &mut dyn Write,
+        reports: &[FileReport],
+        summary: &Summary,
+    ) -> Result<()> {
+        let document = Document {
+            version: SCHEMA_VERSION,
+            issues: issues(reports).collect(),
+            summary: SummaryRecord::new(reports, summary),
+        };
+
+        serde_json::to_writer_pretty(&mut *writer, &document).context("Could not write JSON")?;
+        writeln!(writer)?;
+
+        Ok(())
+    }
+}
+
+impl Reporter for Ndjson {
+    fn write(
+        &self,
+        writer: &mut dyn Write,
+        reports: &[FileReport],
+        summary: &Summary,
+    ) -> Result<()> {
+        let records = issues(reports)
+            .map(Record::Issue)
+            .chain([Record::Summary(SummaryRecord::new(reports, summary))]);
+
+        for record in records {
+            let line = Line {
+                version: SCHEMA_VERSION,
+                record,
+            };
+
+            serde_json::to_writer(&mut *writer, &line).context("Could not write JSON")?;
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{path::PathBuf, time::Duration};
+
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::reporting::test::report;
+
+    fn reports() -> Vec<FileReport> {
+        let clean = FileReport {
+            path: PathBuf::from("src/clean.ts"),
+            source: String::new(),
+            unknown_words: Vec::new(),
+        };
+
+        vec![report(), clean]
+    }
+
+    fn summary() -> Summary {
+        Summary {
+            files: 2,
+            check_time: Duration::from_millis(12),
+            suggest_time: Duration::from_millis(3),
+        }
+    }
+
+    #[test]
+    fn writes_issues_and_summary() {
+        let mut output = Vec::new();
+        Json.write(&mut output, &reports(), &summary()).unwrap();
+        let document: Value = serde_json::from_slice(&output).unwrap();
+
+        assert_eq!(document["version"], SCHEMA_VERSION);
+        assert_eq!(
+            document["issues"][0],
+            json!({
+                "file": "src/typo.ts",
+                "language": "typescript",
+                "rule": "unknown-word",
+                "severity": "warning",
+                "word": "wrlod",
+                "normalized": "wrlod",
+                "start": 21,
+                "end": 26,
+                "line": 2,
+                "column": 10,
+                "node_kind": "comment",
+                "suggestions": ["world", "word"],
+                "layer": null,
+                "replacement": null,
+                "message": null,
+            })
+        );
+        assert_eq!(document["issues"][1]["rule"], "forbidden-word");
+        assert_eq!(document["issues"][1]["layer"], "flag-words");
+        assert_eq!(document["issues"][1]["replacement"], "denylist");
+        assert_eq!(
+            document["summary"],
+            json!({
+                "files": 2,
+                "files_with_issues": 1,
+                "issues": 2,
+                "errors": 1,
+                "warnings": 1,
+                "rules": { "unknown-word": 1, "forbidden-word": 1, "casing": 0 },
+                "timings": { "check": 12.0, "suggest": 3.0 },
+            })
+        );
+    }
+
+    #[test]
+    fn writes_one_record_per_line() {
+        let mut output = Vec::new();
+        Ndjson.write(&mut output, &reports(), &summary()).unwrap();
+
+        let lines = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["type"], "issue");
+        assert_eq!(lines[0]["version"], SCHEMA_VERSION);
+        assert_eq!(lines[0]["word"], "wrlod");
+        assert_eq!(lines[2]["type"], "summary");
+        assert_eq!(lines[2]["issues"], 2);
+    }
+}
@@ -0,0 +1,121 @@
+//! JUnit XML, for CI systems that show test results. Every checked file is
+//! a test case, failing with a `<failure>` per issue in it.
+
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::{Reporter, Summary, column, describe, escape_xml};
+use crate::parsing::parser::FileReport;
+
+pub struct Junit;
+
+impl Reporter for Junit {
+    fn write(
+        &self,
+        writer: &mut dyn Write,
+        reports: &[FileReport],
+        summary: &Summary,
+    ) -> Result<()> {
+        let tests = reports.len();
+        let failures = reports
+            .iter()
+            .filter(|report| !report.unknown_words.is_empty())
+            .count();
+        let time = (summary.check_time + summary.suggest_time).as_secs_f64();
+
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            writer,
+            r#"<testsuites name="rspell" tests="{tests}" failures="{failures}" errors="0" time="{time:.3}">"#
+        )?;
+        writeln!(
+            writer,
+            r#"  <testsuite name="rspell" tests="{tests}" failures="{failures}" errors="0" skipped="0" time="{time:.3}">"#
+        )?;
+
+        for report in reports {
+            let name = escape_xml(&report.path.display().to_string());
+
+            if report.unknown_words.is_empty() {
+                writeln!(
+                    writer,
+                    r#"    <testcase classname="rspell" name="{name}" file="{name}"/>"#
+                )?;
+                continue;
+            }
+
+            writeln!(
+                writer,
+                r#"    <testcase classname="rspell" name="{name}" file="{name}">"#
+            )?;
+
+            for unknown in &report.unknown_words {
+                let location = format!(
+                    "{}:{}:{}",
+                    report.path.display(),
+                    unknown.word.span.line + 1,
+                    column(report, unknown)
+                );
+                let description = describe(report, unknown);
+
+                writeln!(
+                    writer,
+                    r#"      <failure type="{}" message="{}">{}</failure>"#,
+                    unknown.problem.rule().name(),
+                    escape_xml(&description),
+                    escape_xml(&format!("{location}: {description}")),
+                )?;
+            }
+
+            writeln!(writer, "    </testcase>")?;
+        }
+
+        writeln!(writer, "  </testsuite>")?;
+        writeln!(writer, "</testsuites>")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{path::PathBuf, time::Duration};
+
+    use super::*;
+    use crate::reporting::test::report;
+
+    #[test]
+    fn writes_a_test_case_per_file() {
+        let clean = FileReport {
+            path: PathBuf::from("src/clean.ts"),
+            source: String::new(),
+            unknown_words: Vec::new(),
+        };
+        let summary = Summary {
+            files: 2,
+            check_time: Duration::from_millis(1200),
+            suggest_time: Duration::from_millis(34),
+        };
+
+        let mut output = Vec::new();
+        Junit
+            .write(&mut output, &[report(), clean], &summary)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="rspell" tests="2" failures="1" errors="0" time="1.234">
+  <testsuite name="rspell" tests="2" failures="1" errors="0" skipped="0" time="1.234">
+    <testcase classname="rspell" name="src/typo.ts" file="src/typo.ts">
+      <failure type="unknown-word" message="Unknown word &quot;wrlod&quot;, did you mean: world, word?">src/typo.ts:2:10: Unknown word &quot;wrlod&quot;, did you mean: world, word?</failure>
+      <failure type="forbidden-word" message="Forbidden word &quot;blacklist&quot;: Use inclusive language, use &quot;denylist&quot; instead">src/typo.ts:2:18: Forbidden word &quot;blacklist&quot;: Use inclusive language, use &quot;denylist&quot; instead</failure>
+    </testcase>
+    <testcase classname="rspell" name="src/clean.ts" file="src/clean.ts"/>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use std::{
+    collections::HashMap,
+    env,
+    io::{self, IsTerminal, Write},
+    time::Duration,
+};
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::parsing::parser::{FileReport, Problem, Rule, Severity, UnknownWord};
+
+mod checkstyle;
+mod github;
+mod json;
+mod junit;
+mod pretty;
+mod sarif;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Human-readable diagnostics with source snippets
+    #[default]
+    Pretty,
+    /// A single JSON document with every issue and a summary
+    Json,
+    /// One JSON object per line for each issue, then one for the summary
+    Ndjson,
+    /// A SARIF 2.1.0 log, for code scanning tools
+    Sarif,
+    /// GitHub Actions workflow commands, shown as annotations
+    Github,
+    /// Checkstyle XML, with an element per file
+    Checkstyle,
+    /// JUnit XML, with a test case per file
+    Junit,
+}
+
+impl OutputFormat {
+    /// The reporter of the format, for rules of the configured `severities`.
+    fn reporter(self, severities: &HashMap<Rule, Severity>) -> Box<dyn Reporter> {
+        match self {
+            OutputFormat::Pretty => Box::new(pretty::Pretty {
+                color: io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none(),
+            }),
+            OutputFormat::Json => Box::new(json::Json),
+            OutputFormat::Ndjson => Box::new(json::Ndjson),
+            OutputFormat::Sarif => Box::new(sarif::Sarif {
+                severities: severities.clone(),
+            }),
+            OutputFormat::Github => Box::new(github::Github),
+            OutputFormat::Checkstyle => Box::new(checkstyle::Checkstyle),
+            OutputFormat::Junit => Box::new(junit::Junit),
+        }
+    }
+}
+
+/// Writes the outcome of a run in one output format.
+trait Reporter {
+    fn write(
+        &self,
+        writer: &mut dyn Write,
+        reports: &[FileReport],
+        summary: &Summary,
+    ) -> Result<()>;
+}
+
+/// Totals of a run, reported after the issues.
+#[derive(Debug, Clone, Default)]
+pub struct Summary {
+    pub files: usize,
+    pub check_time: Duration,
+    pub suggest_time: Duration,
+}
+
+/// Writes `reports` to stdout in `format`, with rules of the configured
+/// `severities`.
+pub fn emit(
+    reports: &[FileReport],
+    summary: &Summary,
+    format: OutputFormat,
+    severities: &HashMap<Rule, Severity>,
+) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+
+    format
+        .reporter(severities)
+        .write(&mut stdout, reports, summary)?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// The number of issues in `reports` with `severity`.
+pub fn count(reports: &[FileReport], severity: Severity) -> usize {
+    reports
+        .iter()
+        .flat_map(|report| &report.unknown_words)
+        .filter(|unknown| unknown.severity == severity)
+        .count()
+}
+
+/// A one-line description of `unknown`, like `Unknown word "wrlod"`.
+fn title(report: &FileReport, unknown: &UnknownWord) -> String {
+    let span = unknown.word.span;
+    let original = &report.source[span.start..span.end];
+
+    match &unknown.problem {
+        Problem::Unknown => format!("Unknown word \"{original}\""),
+        Problem::Forbidden(_) => format!("Forbidden word \"{original}\""),
+        Problem::Casing { .. } => format!("Wrong casing \"{original}\""),
+    }
+}
+
+/// The title of `unknown` followed by what to do about it, for formats with
+/// a single message per issue.
+fn describe(report: &FileReport, unknown: &UnknownWord) -> String {
+    let mut description = title(report, unknown);
+
+    match &unknown.problem {
+        Problem::Forbidden(forbidden) => {
+            if let Some(message) = &forbidden.message {
+                description.push_str(&format!(": {message}"));
+            }
+            if let Some(replacement) = &forbidden.replacement {
+                description.push_str(&format!(", use \"{replacement}\" instead"));
+            }
+        }
+        Problem::Casing { expected } => {
+            description.push_str(&format!(", should be written as {}", expected.join(" or ")));
+        }
+        Problem::Unknown if !unknown.suggestions.is_empty() => {
+            description.push_str(&format!(
+                ", did you mean: {}?",
+                unknown.suggestions.join(", ")
+            ));
+        }
+        Problem::Unknown => {}
+    }
+
+    description
+}
+
+/// The one-based column of `unknown`, counted in characters.
+fn column(report: &FileReport, unknown: &UnknownWord) -> usize {
+    let span = unknown.word.span;
+
+    report.source[span.start - span.column..span.start]
+        .chars()
+        .count()
+        + 1
+}
+
+/// Escapes `text` for use in XML content and attribute values.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            // Other control characters are not allowed in XML 1.0.
+            char if char.is_control() && char != '\t' && char != '\r' => {}
+            char => escaped.push(char),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::parsing::{
+        parser::Forbidden,
+        word_separator::{Span, Word},
+    };
+
+    /// A report of a file with an unknown word, a warning, and a forbidden
+    /// one, an error, on its second line, which starts at byte 11.
+    pub fn report() -> FileReport {
+        report_at("src/typo.ts", [Severity::Warning, Severity::Error])
+    }
+
+    /// The file of [`report`] at `path`, with the `severities` of its
+    /// unknown and forbidden word.
+    pub fn report_at(path: &str, severities: [Severity; 2]) -> FileReport {
+        let unknown =
+            |text: &str, column: usize, problem, severity, suggestions: &[&str]| UnknownWord {
+                word: Word {
+                    text: text.to_string(),
+                    span: Span {
+                        start: 11 + column,
+                        end: 11 + column + text.len(),
+                        line: 1,
+                        column,
+                    },
+                },
+                node_kind: "comment",
+                language: "typescript",
+                problem,
+                severity,
+                suggestions: suggestions.iter().map(|word| word.to_string()).collect(),
+            };
+
+        FileReport {
+            path: PathBuf::from(path),
+            source: "let x = 1;\n// naïve wrlod, <blacklist>\n".to_string(),
+            unknown_words: vec![
+                unknown(
+                    "wrlod",
+                    10,
+                    Problem::Unknown,
+                    severities[0],
+                    &["world", "word"],
+                ),
+                unknown(
+                    "blacklist",
+                    18,
+                    Problem::Forbidden(Forbidden {
+                        layer: "flag-words".to_string(),
+                        replacement: Some("denylist".to_string()),
+                        message: Some("Use inclusive language".to_string()),
+                    }),
+                    severities[1],
+                    &["denylist"],
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn describes_issues_in_one_line() {
+        let report = report();
+
+        assert_eq!(
+            describe(&report, &report.unknown_words[0]),
+            "Unknown word \"wrlod\", did you mean: world, word?"
+        );
+        assert_eq!(
+            describe(&report, &report.unknown_words[1]),
+            "Forbidden word \"blacklist\": Use inclusive language, use \"denylist\" instead"
+        );
+        assert_eq!(column(&report, &report.unknown_words[0]), 10);
+    }
+
+    #[test]
+    fn escapes_xml() {
+        assert_eq!(
+            escape_xml("<a href=\"x\">Tom & 'Jerry'</a>\n\u{1}"),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &apos;Jerry&apos;&lt;/a&gt;&#10;"
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Human-readable diagnostics in the style of rustc, with source snippets.
+
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use codespan_reporting::{
+    diagnostic::{self, Diagnostic, Label},
+    files::SimpleFiles,
+    term::{
+        self,
+        termcolor::{Ansi, NoColor, WriteColor},
+    },
+};
+
+use super::{Reporter, Summary, title};
+use crate::parsing::parser::{FileReport, Forbidden, Problem, Severity, UnknownWord};
+
+/// Every issue as a rustc-style diagnostic, then the number of files and
+/// the time taken.
+pub struct Pretty {
+    pub color: bool,
+}
+
+impl Reporter for Pretty {
+    fn write(
+        &self,
+        writer: &mut dyn Write,
+        reports: &[FileReport],
+        summary: &Summary,
+    ) -> Result<()> {
+        if self.color {
+            write_diagnostics(&mut Ansi::new(&mut *writer), reports)?;
+        } else {
+            write_diagnostics(&mut NoColor::new(&mut *writer), reports)?;
+        }
+
+        writeln!(
+            writer,
+            "[*] Done with {} files in {:?}",
+            summary.files,
+            summary.check_time + summary.suggest_time
+        )?;
+
+        Ok(())
+    }
+}
+
+pub fn write_diagnostics(writer: &mut dyn WriteColor, reports: &[FileReport]) -> Result<()> {
+    let config = term::Config::default();
+    let mut files = SimpleFiles::new();
+
+    for report in reports {
+        let file_id = files.add(report.path.display().to_string(), report.source.as_str());
+
+        for unknown in &report.unknown_words {
+            term::emit(
+                writer,
+                &config,
+                &files,
+                &diagnostic(file_id, report, unknown),
+            )
+            .context("Could not emit diagnostic")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn diagnostic(file_id: usize, report: &FileReport, unknown: &UnknownWord) -> Diagnostic<usize> {
+    let span = unknown.word.span;
+
+    let label = match &unknown.problem {
+        Problem::Unknown => "not found in any dictionary".to_string(),
+        Problem::Forbidden(forbidden) => forbidden
+            .message
+            .clone()
+            .unwrap_or_else(|| format!("forbidden by the {} dictionaries", forbidden.layer)),
+        Problem::Casing { expected } => {
+            format!("should be written as {}", expected.join(" or "))
+        }
+    };
+
+    let severity = match unknown.severity {
+        Severity::Error => diagnostic::Severity::Error,
+        Severity::Warning => diagnostic::Severity::Warning,
+        Severity::Info | Severity::Off => diagnostic::Severity::Note,
+    };
+
+    let mut diagnostic = Diagnostic::new(severity)
+        .with_message(title(report, unknown))
+        .with_label(Label::primary(file_id, span.start..span.end).with_message(label));
+
+    match &unknown.problem {
+        Problem::Forbidden(Forbidden {
+            replacement: Some(replacement),
+            ..
+        }) => {
+            diagnostic = diagnostic.with_note(format!("use \"{replacement}\" instead"));
+        }
+        // The label already names the expected casing.
+        Problem::Casing { .. } => {}
+        _ if !unknown.suggestions.is_empty() => {
+            diagnostic =
+                diagnostic.with_note(format!("did you mean: {}?", unknown.suggestions.join(", ")));
+        }
+        _ => {}
+    }
+
+    diagnostic
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::parsing::word_separator::{Span, Word};
+
+    #[test]
+    fn renders_unknown_words_with_location_and_suggestions() {
+        let report = FileReport {
+            path: PathBuf::from("src/typo.ts"),
+            source: "let x = 1;\nconsole.log(wrlod);\n".to_string(),
+            unknown_words: vec![UnknownWord {
+                word: Word {
+                    text: "wrlod".to_string(),
+                    span: Span {
+                        start: 23,
+                        end: 28,
+                        line: 1,
+                        column: 12,
+                    },
+                },
+                node_kind: "identifier",
+                language: "typescript",
+                problem: Problem::Unknown,
+                severity: Severity::Warning,
+                suggestions: vec!["world".to_string()],
+            }],
+        };
+
+        let mut writer = NoColor::new(Vec::new());
+        write_diagnostics(&mut writer, &[report]).unwrap();
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+
+        assert!(output.contains("warning: Unknown word \"wrlod\""));
+        assert!(output.contains("src/typo.ts:2:13"));
+        assert!(output.contains("not found in any dictionary"));
+        assert!(output.contains("did you mean: world?"));
+    }
+
+    #[test]
+    fn renders_flagged_words_with_message_and_replacement() {
+        let report = FileReport {
+            path: PathBuf::from("src/list.ts"),
+            source: "let blacklist = [];\n".to_string(),
+            unknown_words: vec![UnknownWord {
+                word: Word {
+                    text: "blacklist".to_string(),
+                    span: Span {
+                        start: 4,
+                        end: 13,
+                        line: 0,
+                        column: 4,
+                    },
+                },
+                node_kind: "identifier",
+                language: "typescript",
+                problem: Problem::Forbidden(Forbidden {
+                    layer: "flag-words".to_string(),
+                    replacement: Some("denylist".to_string()),
+                    message: Some("Use inclusive language".to_string()),
+                }),
+                severity: Severity::Error,
+                suggestions: vec!["denylist".to_string()],
+            }],
+        };
+
+        let mut writer = NoColor::new(Vec::new());
+        write_diagnostics(&mut writer, &[report]).unwrap();
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+
+        assert!(output.contains("error: Forbidden word \"blacklist\""));
+        assert!(output.contains("Use inclusive language"));
+        assert!(output.contains("use \"denylist\" instead"));
+    }
+}
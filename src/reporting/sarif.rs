@@ -0,0 +1,413 @@
+//! Output as a SARIF 2.1.0 log, the format code scanning services like GitHub
+//! ingest. Columns are counted in UTF-16 code units, their default.
+
+use std::{collections::HashMap, io::Write, path::Path};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::{Reporter, Summary, title};
+use crate::parsing::{
+    parser::{FileReport, Problem, Rule, Severity, UnknownWord},
+    word_separator::Span,
+};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+#[derive(Serialize)]
+struct Log<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: [Run<'a>; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool,
+    results: Vec<SarifResult<'a>>,
+    column_kind: &'static str,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    semantic_version: &'static str,
+    rules: Vec<RuleDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RuleDescriptor {
+    id: Rule,
+    name: &'static str,
+    short_description: Text,
+    default_configuration: Configuration,
+}
+
+#[derive(Serialize)]
+struct Configuration {
+    level: &'static str,
+}
+
+#[derive(Serialize)]
+struct Text {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult<'a> {
+    rule_id: Rule,
+    rule_index: usize,
+    level: &'static str,
+    message: Text,
+    locations: [Location<'a>; 1],
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location<'a> {
+    physical_location: PhysicalLocation<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation<'a> {
+    artifact_location: ArtifactLocation,
+    region: Region<'a>,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_column: Option<usize>,
+    byte_offset: usize,
+    byte_length: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<Content<'a>>,
+}
+
+#[derive(Serialize)]
+struct Content<'a> {
+    text: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix<'a> {
+    description: Text,
+    artifact_changes: [ArtifactChange<'a>; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange<'a> {
+    artifact_location: ArtifactLocation,
+    replacements: [Replacement<'a>; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement<'a> {
+    deleted_region: Region<'a>,
+    inserted_content: Content<'a>,
+}
+
+/// The SARIF level of problems of `severity`.
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+        Severity::Off => "none",
+    }
+}
+
+/// A descriptor of every rule, at the configured `severities`.
+fn rules(severities: &HashMap<Rule, Severity>) -> Vec<RuleDescriptor> {
+    Rule::ALL
+        .into_iter()
+        .map(|rule| {
+            let (name, description) = match rule {
+                Rule::UnknownWord => ("UnknownWord", "The word is not in any dictionary."),
+                Rule::ForbiddenWord => (
+                    "ForbiddenWord",
+                    "The word is forbidden by a dictionary layer, like flag-words.",
+                ),
+                Rule::Casing => (
+                    "Casing",
+                    "The word is not written in the casing its dictionary entry requires.",
+                ),
+            };
+
+            RuleDescriptor {
+                id: rule,
+                name,
+                short_description: Text {
+                    text: description.to_string(),
+                },
+                default_configuration: Configuration {
+                    level: level(severities.get(&rule).copied().unwrap_or_default()),
+                },
+            }
+        })
+        .collect()
+}
+
+/// The URI of `path`, relative to the directory rspell runs in unless it is
+/// absolute.
+fn artifact_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let path = path.strip_prefix("./").unwrap_or(&path);
+
+    // Everything but the characters allowed in a URI path is percent-encoded,
+    // including every byte of non-ASCII characters.
+    let mut uri = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/!$&'()*+,;=:@".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+
+    if uri.starts_with('/') {
+        format!("file://{uri}")
+    } else {
+        uri
+    }
+}
+
+/// The one-based UTF-16 column of byte `offset` on the line `span` starts
+/// on.
+fn utf16_column(source: &str, span: Span, offset: usize) -> usize {
+    let line_start = span.start - span.column;
+
+    source[line_start..offset].encode_utf16().count() + 1
+}
+
+fn result<'a>(report: &'a FileReport, unknown: &'a UnknownWord) -> SarifResult<'a> {
+    let span = unknown.word.span;
+    let uri = artifact_uri(&report.path);
+    let rule = unknown.problem.rule();
+
+    let message = match &unknown.problem {
+        Problem::Forbidden(forbidden) if forbidden.message.is_some() => format!(
+            "{}: {}",
+            title(report, unknown),
+            forbidden.message.as_deref().unwrap_or_default()
+        ),
+        _ => title(report, unknown),
+    };
+
+    let fixes = unknown
+        .suggestions
+        .first()
+        .map(|suggestion| Fix {
+            description: Text {
+                text: format!("Replace with \"{suggestion}\""),
+            },
+            artifact_changes: [ArtifactChange {
+                artifact_location: ArtifactLocation { uri: uri.clone() },
+                replacements: [Replacement {
+                    deleted_region: Region {
+                        start_line: None,
+                        start_column: None,
+                        end_column: None,
+                        byte_offset: span.start,
+                        byte_length: span.end - span.start,
+                        snippet: None,
+                    },
+                    inserted_content: Content { text: suggestion },
+                }],
+            }],
+        })
+        .into_iter()
+        .collect();
+
+    SarifResult {
+        rule_id: rule,
+        rule_index: Rule::ALL.iter().position(|other| *other == rule).unwrap(),
+        level: level(unknown.severity),
+        message: Text { text: message },
+        locations: [Location {
+            physical_location: PhysicalLocation {
+                artifact_location: ArtifactLocation { uri },
+                region: Region {
+                    start_line: Some(span.line + 1),
+                    start_column: Some(utf16_column(&report.source, span, span.start)),
+                    end_column: Some(utf16_column(&report.source, span, span.end)),
+                    byte_offset: span.start,
+                    byte_length: span.end - span.start,
+                    snippet: Some(Content {
+                        text: &report.source[span.start..span.end],
+                    }),
+                },
+            },
+        }],
+        fixes,
+    }
+}
+
+pub struct Sarif {
+    /// The configured severity of each rule, `Error` unless given.
+    pub severities: HashMap<Rule, Severity>,
+}
+
+impl Reporter for Sarif {
+    fn write(&self, writer: &mut dyn Write, reports: &[FileReport], _: &Summary) -> Result<()> {
+        let version = env!("CARGO_PKG_VERSION");
+
+        let log = Log {
+            schema: SCHEMA,
+            version: "2.1.0",
+            runs: [Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: env!("CARGO_PKG_NAME"),
+                        version,
+                        semantic_version: version,
+                        rules: rules(&self.severities),
+                    },
+                },
+                results: reports
+                    .iter()
+                    .flat_map(|report| {
+                        report
+                            .unknown_words
+                            .iter()
+                            .map(move |unknown| result(report, unknown))
+                    })
+                    .collect(),
+                column_kind: "utf16CodeUnits",
+            }],
+        };
+
+        serde_json::to_writer_pretty(&mut *writer, &log).context("Could not write SARIF")?;
+        writeln!(writer)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::reporting::test::report_at;
+
+    fn report() -> FileReport {
+        report_at("./src/naïve.ts", [Severity::Warning, Severity::Info])
+    }
+
+    fn sarif(reports: &[FileReport]) -> Value {
+        let mut output = Vec::new();
+        let sarif = Sarif {
+            severities: HashMap::from([(Rule::UnknownWord, Severity::Warning)]),
+        };
+        sarif
+            .write(&mut output, reports, &Summary::default())
+            .unwrap();
+
+        serde_json::from_slice(&output).unwrap()
+    }
+
+    // TODO: replace the schema with sarif-schema-2.1.0.json, unchanged, from
+    // the schemas published with the OASIS SARIF 2.1.0 specification. It is a
+    // hand-written subset of it for now, as no copy was at hand offline, and
+    // may accept or reject logs differently than the official schema.
+    #[test]
+    fn validates_against_the_schema() {
+        let schema =
+            serde_json::from_str(include_str!("../../testfiles/sarif-2.1.0.schema.json")).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        for log in [sarif(&[report()]), sarif(&[])] {
+            let errors = validator
+                .iter_errors(&log)
+                .map(|error| format!("{error} at {}", error.instance_path))
+                .collect::<Vec<_>>();
+
+            assert!(errors.is_empty(), "{errors:#?}");
+        }
+
+        // The schema does reject what is not SARIF.
+        let mut invalid = sarif(&[report()]);
+        invalid["runs"][0]["results"][0]["level"] = "severe".into();
+        assert!(!validator.is_valid(&invalid));
+    }
+
+    #[test]
+    fn describes_results_and_fixes() {
+        let log = sarif(&[report()]);
+        let run = &log["runs"][0];
+
+        assert_eq!(run["tool"]["driver"]["name"], "rspell");
+        assert_eq!(run["tool"]["driver"]["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(run["tool"]["driver"]["rules"][2]["id"], "casing");
+
+        // Rules are described at their configured severity.
+        let levels = run["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|rule| rule["defaultConfiguration"]["level"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(levels, ["warning", "error", "error"]);
+
+        let unknown = &run["results"][0];
+        assert_eq!(unknown["ruleId"], "unknown-word");
+        assert_eq!(unknown["level"], "warning");
+        assert_eq!(unknown["message"]["text"], "Unknown word \"wrlod\"");
+
+        let location = &unknown["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/na%C3%AFve.ts");
+        // "ï" is two bytes, but one UTF-16 code unit.
+        assert_eq!(location["region"]["startLine"], 2);
+        assert_eq!(location["region"]["startColumn"], 10);
+        assert_eq!(location["region"]["endColumn"], 15);
+
+        let replacement = &unknown["fixes"][0]["artifactChanges"][0]["replacements"][0];
+        assert_eq!(replacement["deletedRegion"]["byteOffset"], 21);
+        assert_eq!(replacement["insertedContent"]["text"], "world");
+
+        let forbidden = &run["results"][1];
+        assert_eq!(forbidden["ruleIndex"], 1);
+        assert_eq!(forbidden["level"], "note");
+        assert_eq!(
+            forbidden["message"]["text"],
+            "Forbidden word \"blacklist\": Use inclusive language"
+        );
+
+        // Words without suggestions have no fix.
+        let mut report = report();
+        report.unknown_words[0].suggestions.clear();
+        assert!(
+            sarif(&[report])["runs"][0]["results"][0]
+                .get("fixes")
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::HashSet;
+
+use fst::Automaton;
+use rayon::{
+    iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator},
+    slice::ParallelSliceMut,
+};
+
+/// The largest edit distance at which a word is suggested. The deletes
+/// generated by `for_each_delete` are tied to this.
+const MAX_DISTANCE: usize = 2;
+
+/// Only this many leading characters of a word are indexed. Longer words
+/// still match, as an edit past the prefix leaves the prefix unchanged, and
+/// it keeps the index from growing with the length of the words.
+const PREFIX_LENGTH: usize = 7;
+
+/// Finds dictionary words close to a misspelling, using the symmetric delete
+/// algorithm from SymSpell. Every word is indexed under the strings produced
+/// by deleting up to [`MAX_DISTANCE`] characters from its prefix. Two words
+/// within that distance of each other share at least one such delete, so a
+/// lookup only has to compare against the words sharing a delete with the
+/// input, rather than the whole dictionary.
+pub struct Suggester {
+    words: Vec<Box<[char]>>,
+    /// The hash of every delete and the index of the word it came from,
+    /// sorted by hash. Collisions only add candidates, which are filtered by
+    /// their actual distance.
+    deletes: Vec<(u64, u32)>,
+}
+
+impl Suggester {
+    pub fn new<S: AsRef<str>>(words: impl IntoIterator<Item = S>) -> Self {
+        let words = words
+            .into_iter()
+            .map(|word| word.as_ref().chars().collect())
+            .collect::<Vec<Box<[char]>>>();
+
+        let mut deletes = words
+            .par_iter()
+            .enumerate()
+            .flat_map_iter(|(index, chars)| {
+                let mut hashes = Vec::new();
+                for_each_delete(prefix(chars), |hash| hashes.push((hash, index as u32)));
+                hashes
+            })
+            .collect::<Vec<_>>();
+
+        // A word can produce the same delete several times, e.g. "aab".
+        deletes.par_sort_unstable();
+        deletes.dedup();
+
+        Suggester { words, deletes }
+    }
+
+    /// Up to `limit` words closest to `word` by Damerau-Levenshtein
+    /// distance, best first. Ties are broken alphabetically.
+    pub fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let chars = word.chars().collect::<Vec<_>>();
+
+        let mut seen = HashSet::new();
+        let mut suggestions = Vec::new();
+
+        for_each_delete(prefix(&chars), |hash| {
+            let start = self.deletes.partition_point(|(delete, _)| *delete < hash);
+            let candidates = self.deletes[start..]
+                .iter()
+                .take_while(|(delete, _)| *delete == hash);
+
+            for &(_, index) in candidates {
+                if !seen.insert(index) {
+                    continue;
+                }
+
+                let candidate = &self.words[index as usize];
+                if candidate.len().abs_diff(chars.len()) > MAX_DISTANCE || **candidate == *chars {
+                    continue;
+                }
+
+                let distance = damerau_levenshtein(&chars, candidate);
+                if distance <= MAX_DISTANCE {
+                    suggestions.push((distance, candidate.iter().collect::<String>()));
+                }
+            }
+        });
+
+        suggestions.sort();
+        suggestions
+            .into_iter()
+            .take(limit)
+            .map(|(_, suggestion)| suggestion)
+            .collect()
+    }
+}
+
+/// An `fst` automaton accepting the words within [`MAX_DISTANCE`] of a word,
+/// to search a set, like a compiled dictionary, without indexing it first.
+/// It reads the words of the set a character at a time, with the last two
+/// rows of the Damerau-Levenshtein matrix as its state.
+pub struct DamerauLevenshtein {
+    word: Vec<char>,
+}
+
+#[derive(Clone)]
+pub struct DistanceState {
+    /// The distances of every prefix of the word to the characters read.
+    row: Vec<usize>,
+    /// The row before the last character read, for transpositions.
+    previous: Vec<usize>,
+    last: Option<char>,
+    /// The bytes of a character that is not read completely yet.
+    pending: ([u8; 4], usize),
+}
+
+impl DamerauLevenshtein {
+    pub fn new(word: &str) -> Self {
+        DamerauLevenshtein {
+            word: word.chars().collect(),
+        }
+    }
+
+    fn read(&self, state: &DistanceState, char: char) -> DistanceState {
+        let word = &self.word;
+        let mut row = vec![state.row[0] + 1; word.len() + 1];
+
+        for i in 1..=word.len() {
+            let cost = usize::from(word[i - 1] != char);
+
+            row[i] = (state.row[i] + 1)
+                .min(row[i - 1] + 1)
+                .min(state.row[i - 1] + cost);
+
+            if i > 1 && state.last == Some(word[i - 1]) && word[i - 2] == char {
+                row[i] = row[i].min(state.previous[i - 2] + 1);
+            }
+        }
+
+        DistanceState {
+            previous: state.row.clone(),
+            row,
+            last: Some(char),
+            pending: ([0; 4], 0),
+        }
+    }
+}
+
+impl Automaton for DamerauLevenshtein {
+    type State = DistanceState;
+
+    fn start(&self) -> DistanceState {
+        DistanceState {
+            row: (0..=self.word.len()).collect(),
+            previous: Vec::new(),
+            last: None,
+            pending: ([0; 4], 0),
+        }
+    }
+
+    fn is_match(&self, state: &DistanceState) -> bool {
+        state.pending.1 == 0 && state.row[self.word.len()] <= MAX_DISTANCE
+    }
+
+    fn can_match(&self, state: &DistanceState) -> bool {
+        state.row.iter().any(|distance| *distance <= MAX_DISTANCE)
+    }
+
+    fn accept(&self, state: &DistanceState, byte: u8) -> DistanceState {
+        let (mut bytes, mut length) = state.pending;
+        bytes[length] = byte;
+        length += 1;
+
+        let expected = match bytes[0] {
+            0x00..=0x7f => 1,
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            _ => 4,
+        };
+
+        if length < expected {
+            return DistanceState {
+                pending: (bytes, length),
+                ..state.clone()
+            };
+        }
+
+        let char = std::str::from_utf8(&bytes[..length])
+            .ok()
+            .and_then(|char| char.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER);
+
+        self.read(state, char)
+    }
+}
+
+fn prefix(chars: &[char]) -> &[char] {
+    &chars[..chars.len().min(PREFIX_LENGTH)]
+}
+
+/// Calls `visit` with the hash of `chars` and of every string produced by
+/// deleting one or two characters from it. The hashes are computed without
+/// building the strings, as this runs for every word in the dictionary.
+fn for_each_delete(chars: &[char], mut visit: impl FnMut(u64)) {
+    const NONE: usize = usize::MAX;
+
+    let hash = |skip_a: usize, skip_b: usize| {
+        chars
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != skip_a && *index != skip_b)
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, (_, char)| {
+                (hash ^ u64::from(*char)).wrapping_mul(0x0100_0000_01b3)
+            })
+    };
+
+    visit(hash(NONE, NONE));
+
+    for a in 0..chars.len() {
+        visit(hash(a, NONE));
+
+        for b in a + 1..chars.len() {
+            visit(hash(a, b));
+        }
+    }
+}
+
+/// The optimal string alignment variant of the Damerau-Levenshtein distance:
+/// the number of insertions, deletions, substitutions and transpositions of
+/// adjacent characters needed to turn `a` into `b`.
+pub fn damerau_levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous_previous = vec![0; b.len() + 1];
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(previous_previous[j - 2] + 1);
+            }
+        }
+
+        (previous_previous, previous, current) = (previous, current, previous_previous);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use fst::IntoStreamer;
+
+    use super::*;
+
+    fn distance(a: &str, b: &str) -> usize {
+        damerau_levenshtein(
+            &a.chars().collect::<Vec<_>>(),
+            &b.chars().collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn computes_damerau_levenshtein_distance() {
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("hello", "hello"), 0);
+        assert_eq!(distance("helo", "hello"), 1);
+        assert_eq!(distance("teh", "the"), 1);
+        assert_eq!(distance("wrlod", "world"), 2);
+        assert_eq!(distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn suggests_closest_words_first() {
+        let suggester = Suggester::new(["greet", "great", "world", "word", "hello", "grease"]);
+
+        assert_eq!(suggester.suggest("gret", 5), ["great", "greet"]);
+        assert_eq!(suggester.suggest("wrlod", 5), ["world"]);
+        assert_eq!(suggester.suggest("helo", 1), ["hello"]);
+        assert!(suggester.suggest("xyzzy", 5).is_empty());
+    }
+
+    #[test]
+    fn searches_sets_by_distance() {
+        let set = fst::Set::from_iter(["café", "great", "greet", "the", "world"]).unwrap();
+        let search = |word: &str| {
+            let mut matches = set
+                .search(DamerauLevenshtein::new(word))
+                .into_stream()
+                .into_strs()
+                .unwrap();
+            matches.sort();
+            matches
+        };
+
+        assert_eq!(search("gret"), ["great", "greet"]);
+        assert_eq!(search("wrlod"), ["world"]);
+        assert_eq!(search("teh"), ["the"]);
+        assert_eq!(search("cafe"), ["café"]);
+        assert!(search("xyzzy").is_empty());
+    }
+
+    #[test]
+    fn suggests_for_edits_past_the_prefix() {
+        let suggester = Suggester::new(["internationalization"]);
+
+        assert_eq!(
+            suggester.suggest("internationalizaton", 5),
+            ["internationalization"]
+        );
+    }
+}